@@ -0,0 +1,68 @@
+//! Compares the `const` validation routines with the runtime ones used by
+//! the `TryFrom` impls and by `AbsPath::normalize()`.
+
+use abs_path::{AbsPath, NodeName};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+fn inputs() -> Vec<(&'static str, String)> {
+    let long_name = "a".repeat(200);
+    let deep = "/component".repeat(64);
+    let file_list_entry = "/home/user/projects/abs-path/target/debug/deps/\
+                           libabs_path_core-0123456789abcdef.rlib"
+        .to_owned();
+    vec![
+        ("short", "/foo/bar".to_owned()),
+        ("long_name", format!("/{long_name}")),
+        ("deep", deep),
+        ("file_list_entry", file_list_entry),
+    ]
+}
+
+fn abs_path_from_str(c: &mut Criterion) {
+    let mut group = c.benchmark_group("abs_path_from_str");
+    for (name, input) in inputs() {
+        group.bench_with_input(
+            BenchmarkId::new("const", name),
+            &input,
+            |b, i| b.iter(|| AbsPath::from_str(std::hint::black_box(i))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("runtime", name),
+            &input,
+            |b, i| b.iter(|| <&AbsPath>::try_from(std::hint::black_box(&**i))),
+        );
+    }
+    group.finish();
+}
+
+fn node_name_from_str(c: &mut Criterion) {
+    let mut group = c.benchmark_group("node_name_from_str");
+    for (name, input) in [("short", "foo.rs"), ("long", &*"a".repeat(200))] {
+        group.bench_with_input(
+            BenchmarkId::new("const", name),
+            input,
+            |b, i| b.iter(|| NodeName::from_str(std::hint::black_box(i))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("runtime", name),
+            input,
+            |b, i| b.iter(|| <&NodeName>::try_from(std::hint::black_box(i))),
+        );
+    }
+    group.finish();
+}
+
+fn normalize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("normalize");
+    let mut inputs = inputs();
+    inputs.push(("dots", "/foo/./bar/../baz//qux/.".repeat(16)));
+    for (name, input) in inputs {
+        group.bench_with_input(name, &input, |b, i| {
+            b.iter(|| AbsPath::normalize(std::hint::black_box(i)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, abs_path_from_str, node_name_from_str, normalize);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+//! Items used by the code generated by the `abs-path-macros` crate. They're
+//! not part of the public API.
+
+#[cfg(feature = "alloc")]
+pub use crate::format_path::FormatPath;
+#[cfg(feature = "std")]
+pub use crate::resolve_paths::{PathResolver, ResolveOptions};
+use crate::{MAIN_SEPARATOR_STR, r#const};
+
+/// Returns the length of the path obtained by joining the given parts with
+/// [`path_concat`].
+pub const fn path_len(parts: &[&str]) -> usize {
+    let mut len = 0;
+    let mut idx = 0;
+    while idx < parts.len() {
+        if needs_separator(parts, idx) {
+            len += MAIN_SEPARATOR_STR.len();
+        }
+        len += parts[idx].len();
+        idx += 1;
+    }
+    len
+}
+
+/// Joins the given parts with the path separator into a byte array.
+///
+/// The first part must be a valid absolute path, and every other part must be
+/// a non-empty sequence of valid file names joined by the path separator.
+/// `N` must be equal to [`path_len`] of the same parts.
+pub const fn path_concat<const N: usize>(parts: &[&str]) -> [u8; N] {
+    let mut buf = [0; N];
+    let mut offset = 0;
+    let mut idx = 0;
+    while idx < parts.len() {
+        if needs_separator(parts, idx) {
+            offset = copy_into(&mut buf, offset, MAIN_SEPARATOR_STR);
+        }
+        offset = copy_into(&mut buf, offset, parts[idx]);
+        idx += 1;
+    }
+    assert!(offset == N, "buffer length doesn't match the path length");
+    buf
+}
+
+/// Returns whether a separator has to be inserted before the part at the
+/// given index.
+const fn needs_separator(parts: &[&str], idx: usize) -> bool {
+    match idx {
+        0 => false,
+        1 => !r#const::str_eq(parts[0], MAIN_SEPARATOR_STR),
+        _ => true,
+    }
+}
+
+const fn copy_into<const N: usize>(
+    buf: &mut [u8; N],
+    mut offset: usize,
+    str: &str,
+) -> usize {
+    let bytes = str.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        buf[offset] = bytes[idx];
+        offset += 1;
+        idx += 1;
+    }
+    offset
+}
@@ -1,17 +1,24 @@
+#[cfg(feature = "alloc")]
 use alloc::borrow::{Cow, ToOwned};
 use core::error::Error;
 use core::fmt;
-use core::ops::{Deref, Range};
+use core::ops::Deref;
+#[cfg(feature = "alloc")]
+use core::ops::Range;
 
+#[cfg(feature = "alloc")]
 use compact_str::CompactString;
 
+use crate::node_name::INVALID_CHARACTERS;
+#[cfg(feature = "alloc")]
+use crate::{AbsPathBuf, GenericAbsPathBuf, PathStorage};
 use crate::{
-    AbsPathBuf,
     InvalidNodeNameError,
     MAIN_SEPARATOR_CHAR,
     MAIN_SEPARATOR_STR,
     NodeName,
     r#const,
+    scan,
 };
 
 /// The borrowed version of [`AbsPathBuf`].
@@ -24,20 +31,48 @@ pub struct Components<'path> {
     inner: &'path str,
 }
 
-/// TODO: docs.
+/// The error type returned when converting a [`Path`](std::path::Path) to an
+/// [`AbsPath`] fails.
 #[cfg(feature = "std")]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum AbsPathFromPathError {
     /// The path is not absolute.
     NotAbsolute,
 
+    /// The path is absolute, but it's not a valid [`AbsPath`], e.g. because
+    /// it contains a `.` component.
+    ///
+    /// The wrapped error is never [`InvalidAbsPathError::NotAbsolute`], which
+    /// is reported as [`NotAbsolute`](Self::NotAbsolute) instead.
+    Invalid(InvalidAbsPathError),
+
     /// The path is not valid UTF-8.
     NotUtf8,
 }
 
-/// TODO: docs.
+/// The error type returned when converting a string to an [`AbsPath`] fails.
+///
+/// Component indices are zero-based and don't count the root, so in
+/// `/foo/bar` the component `foo` has index 0.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct AbsPathNotAbsoluteError;
+pub enum InvalidAbsPathError {
+    /// The path doesn't start with a path separator.
+    NotAbsolute,
+
+    /// The path contains an empty component starting at the given byte
+    /// offset, e.g. because of two consecutive separators or a trailing
+    /// separator.
+    EmptyComponent { byte_offset: usize, component_idx: usize },
+
+    /// The path contains a `.` component starting at the given byte offset.
+    SingleDotComponent { byte_offset: usize, component_idx: usize },
+
+    /// The path contains a `..` component starting at the given byte offset.
+    DoubleDotComponent { byte_offset: usize, component_idx: usize },
+
+    /// The path contains an invalid character at the given byte offset.
+    InvalidCharacter { byte_offset: usize, component_idx: usize, ch: char },
+}
 
 /// The type of error that can occur when [`normalizing`](AbsPath::normalize) a
 /// path.
@@ -53,6 +88,7 @@ pub enum NormalizeError {
     NotAbsolute,
 }
 
+#[cfg(feature = "alloc")]
 struct NormalizeState<'a> {
     /// The offset in the original string up to which components have been
     /// processed. If it's less then the length of the original string, then
@@ -66,6 +102,7 @@ struct NormalizeState<'a> {
     original_str: &'a str,
 }
 
+#[cfg(feature = "alloc")]
 enum NormalizedPath {
     Alloc(CompactString),
     /// A byte range in the [original string](NormalizeState::original_str)
@@ -82,6 +119,7 @@ impl AbsPath {
     }
 
     /// TODO: docs.
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn concat<'other>(&self, other: &'other Self) -> Cow<'other, Self> {
         if self == Self::root() {
@@ -97,41 +135,65 @@ impl AbsPath {
         Components { inner: self.as_str() }
     }
 
-    /// TODO: docs.
+    /// Returns whether the two paths are equal.
+    ///
+    /// This is the same as `self == other`, but it can be called in `const`
+    /// contexts.
+    #[inline]
+    pub const fn eq_const(&self, other: &Self) -> bool {
+        r#const::str_eq(self.as_str(), other.as_str())
+    }
+
+    /// Checks that the given string is a valid absolute path.
+    ///
+    /// This can be called in `const` contexts, but outside of them
+    /// `<&AbsPath>::try_from()` should be preferred, since it's faster on
+    /// long paths.
+    ///
+    /// Paths with a trailing separator, like `/foo/`, are rejected with
+    /// [`InvalidAbsPathError::EmptyComponent`]. They used to be accepted,
+    /// which meant two equal paths could have different string
+    /// representations; use [`normalize`](Self::normalize) to strip the
+    /// separator instead.
     #[inline]
-    pub const fn from_str(
-        str: &str,
-    ) -> Result<&Self, AbsPathNotAbsoluteError> {
+    pub const fn from_str(str: &str) -> Result<&Self, InvalidAbsPathError> {
         let mut separator_offsets =
             r#const::str_char_offsets(str, MAIN_SEPARATOR_CHAR);
 
-        let Some(offset) = separator_offsets.next() else {
-            // The string doesn't contain the path separator.
-            return Err(AbsPathNotAbsoluteError);
-        };
-        if offset != 0 {
+        let Some(0) = separator_offsets.next() else {
             // The string doesn't start with the path separator.
-            return Err(AbsPathNotAbsoluteError);
-        }
+            return Err(InvalidAbsPathError::NotAbsolute);
+        };
 
         let separator_len = MAIN_SEPARATOR_STR.len();
-        let mut valid_up_to = separator_len;
 
-        while let Some(offset) = separator_offsets.next() {
-            let component = r#const::str_slice(str, valid_up_to..offset);
-            if NodeName::from_str(component).is_err() {
-                // The string contains an invalid component.
-                return Err(AbsPathNotAbsoluteError);
-            }
-            valid_up_to = offset + separator_len;
+        if str.len() == separator_len {
+            return Ok(Self::root());
         }
 
-        let last_component = r#const::str_slice(str, valid_up_to..str.len());
-        if !last_component.is_empty()
-            && NodeName::from_str(last_component).is_err()
-        {
-            // The string contains an invalid component.
-            return Err(AbsPathNotAbsoluteError);
+        let mut component_start = separator_len;
+        let mut component_idx = 0;
+
+        loop {
+            let component_end = match separator_offsets.next() {
+                Some(offset) => offset,
+                None => str.len(),
+            };
+            let component =
+                r#const::str_slice(str, component_start..component_end);
+            if let Err(err) = NodeName::from_str(component) {
+                return Err(InvalidAbsPathError::from_node_name_error(
+                    err,
+                    component,
+                    component_start,
+                    component_idx,
+                ));
+            }
+            if component_end == str.len() {
+                break;
+            }
+            component_start = component_end + separator_len;
+            component_idx += 1;
         }
 
         // SAFETY: just checked that the string is a valid absolute path.
@@ -147,6 +209,7 @@ impl AbsPath {
     }
 
     /// TODO: docs.
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn join(&self, node_name: &NodeName) -> AbsPathBuf {
         let mut path = self.to_owned();
@@ -161,6 +224,7 @@ impl AbsPath {
     }
 
     /// TODO: docs.
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn normalize(str: &str) -> Result<Cow<'_, Self>, NormalizeError> {
         let mut state = NormalizeState::new(str)?;
@@ -197,21 +261,22 @@ impl AbsPath {
         }
     }
 
-    /// TODO: docs.
+    /// Returns whether `base` is a prefix of this path, component-wise.
     #[inline]
     pub fn starts_with<P>(&self, base: P) -> bool
     where
         P: AsRef<AbsPath>,
     {
-        let base = base.as_ref();
+        self.starts_with_const(base.as_ref())
+    }
 
-        base.is_root()
-            || (self.as_str().starts_with(base.as_str())
-                && self.as_str()[base.len()..]
-                    .chars()
-                    .next()
-                    .map(|c| c == MAIN_SEPARATOR_CHAR)
-                    .unwrap_or(true))
+    /// Returns whether `base` is a prefix of this path, component-wise.
+    ///
+    /// This is the same as [`starts_with`](Self::starts_with), but it can be
+    /// called in `const` contexts.
+    #[inline]
+    pub const fn starts_with_const(&self, base: &Self) -> bool {
+        self.strip_prefix(base).is_some()
     }
 
     /// TODO: docs.
@@ -250,6 +315,80 @@ impl AbsPath {
     }
 }
 
+impl InvalidAbsPathError {
+    /// Returns the byte offset in the original string at which the error
+    /// occurred.
+    ///
+    /// For [`InvalidCharacter`](Self::InvalidCharacter) this is the offset of
+    /// the character itself, for the other component errors it's the offset
+    /// at which the component starts, and for
+    /// [`NotAbsolute`](Self::NotAbsolute) it's always 0.
+    #[inline]
+    pub const fn byte_offset(&self) -> usize {
+        match *self {
+            Self::NotAbsolute => 0,
+            Self::EmptyComponent { byte_offset, .. }
+            | Self::SingleDotComponent { byte_offset, .. }
+            | Self::DoubleDotComponent { byte_offset, .. }
+            | Self::InvalidCharacter { byte_offset, .. } => byte_offset,
+        }
+    }
+
+    /// Returns the index of the component that caused the error, or `None` if
+    /// the path is not absolute.
+    #[inline]
+    pub const fn component_idx(&self) -> Option<usize> {
+        match *self {
+            Self::NotAbsolute => None,
+            Self::EmptyComponent { component_idx, .. }
+            | Self::SingleDotComponent { component_idx, .. }
+            | Self::DoubleDotComponent { component_idx, .. }
+            | Self::InvalidCharacter { component_idx, .. } => {
+                Some(component_idx)
+            },
+        }
+    }
+
+    #[inline]
+    const fn from_node_name_error(
+        err: InvalidNodeNameError,
+        component: &str,
+        component_start: usize,
+        component_idx: usize,
+    ) -> Self {
+        let byte_offset = component_start;
+        match err {
+            InvalidNodeNameError::Empty => {
+                Self::EmptyComponent { byte_offset, component_idx }
+            },
+            InvalidNodeNameError::SingleDot => {
+                Self::SingleDotComponent { byte_offset, component_idx }
+            },
+            InvalidNodeNameError::DoubleDot => {
+                Self::DoubleDotComponent { byte_offset, component_idx }
+            },
+            InvalidNodeNameError::ContainsInvalidCharacter(ch) => {
+                let offset_in_component = match r#const::str_find_char(
+                    component,
+                    INVALID_CHARACTERS,
+                ) {
+                    Some((offset, _)) => offset,
+                    None => 0,
+                };
+                Self::InvalidCharacter {
+                    byte_offset: byte_offset + offset_in_component,
+                    component_idx,
+                    ch,
+                }
+            },
+            InvalidNodeNameError::NotPortable(_) => {
+                // Only returned by conversions from `PortabilityError`.
+                unreachable!()
+            },
+        }
+    }
+}
+
 impl<'path> Components<'path> {
     /// TODO: docs.
     #[inline]
@@ -258,8 +397,44 @@ impl<'path> Components<'path> {
         unsafe { AbsPath::from_str_unchecked(self.inner) }
     }
 
+    /// Same as [`Iterator::next`], but it can be called in `const`
+    /// contexts.
     #[inline]
-    const fn next_back_const(&mut self) -> Option<&'path NodeName> {
+    pub const fn next_const(&mut self) -> Option<&'path NodeName> {
+        let inner = self.inner;
+
+        debug_assert!(r#const::str_starts_with_str(inner, MAIN_SEPARATOR_STR));
+
+        let separator_len = MAIN_SEPARATOR_STR.len();
+
+        if inner.len() == separator_len {
+            return None;
+        }
+
+        let bytes = inner.as_bytes();
+        let mut component_end = separator_len;
+        while component_end < bytes.len()
+            && bytes[component_end] != MAIN_SEPARATOR_CHAR as u8
+        {
+            component_end += 1;
+        }
+
+        let component =
+            r#const::str_slice(inner, separator_len..component_end);
+
+        self.inner = if component_end < inner.len() {
+            r#const::str_slice(inner, component_end..inner.len())
+        } else {
+            MAIN_SEPARATOR_STR
+        };
+
+        Some(unsafe { NodeName::from_str_unchecked(component) })
+    }
+
+    /// Same as [`DoubleEndedIterator::next_back`], but it can be called in
+    /// `const` contexts.
+    #[inline]
+    pub const fn next_back_const(&mut self) -> Option<&'path NodeName> {
         let inner = self.inner;
 
         debug_assert!(r#const::str_starts_with_str(inner, MAIN_SEPARATOR_STR));
@@ -290,6 +465,7 @@ impl<'path> Components<'path> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> NormalizeState<'a> {
     #[inline]
     fn finish(self) -> Cow<'a, AbsPath> {
@@ -320,14 +496,12 @@ impl<'a> NormalizeState<'a> {
 
     #[inline]
     fn process_component(&mut self) -> Result<bool, NormalizeError> {
-        let (component_len, is_last_component) =
-            match self.original_str.as_bytes()[self.cursor..]
-                .iter()
-                .position(|&b| b == MAIN_SEPARATOR_CHAR as u8)
-            {
-                Some(pos) => (pos, false),
-                None => (self.original_str.len() - self.cursor, true),
-            };
+        let (component_len, is_last_component) = match scan::find_separator(
+            &self.original_str.as_bytes()[self.cursor..],
+        ) {
+            Some(pos) => (pos, false),
+            None => (self.original_str.len() - self.cursor, true),
+        };
 
         Self::push_component(
             &mut self.normalized_path,
@@ -358,7 +532,7 @@ impl<'a> NormalizeState<'a> {
 
         let component = &original_str[component_range.clone()];
 
-        let Err(err) = NodeName::from_str(component) else {
+        let Err(err) = scan::node_name_from_str(component) else {
             match normalized_path {
                 NormalizedPath::Alloc(str) => {
                     if str != MAIN_SEPARATOR_STR {
@@ -403,12 +577,17 @@ impl<'a> NormalizeState<'a> {
                 return Ok(());
             },
             InvalidNodeNameError::ContainsInvalidCharacter(ch) => {
+                let offset_in_component = component.find(ch).unwrap_or(0);
                 return Err(NormalizeError::InvalidCharacter {
-                    byte_offset: component_range.start,
+                    byte_offset: component_range.start + offset_in_component,
                     ch,
                 });
             },
             InvalidNodeNameError::DoubleDot => {},
+            InvalidNodeNameError::NotPortable(_) => {
+                // Only returned by conversions from `PortabilityError`.
+                unreachable!()
+            },
         }
 
         let current_path = match normalized_path {
@@ -449,6 +628,7 @@ impl<'a> NormalizeState<'a> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl ToOwned for AbsPath {
     type Owned = AbsPathBuf;
 
@@ -503,9 +683,10 @@ impl fmt::Display for AbsPath {
     }
 }
 
-impl PartialEq<AbsPathBuf> for AbsPath {
+#[cfg(feature = "alloc")]
+impl<S: PathStorage> PartialEq<GenericAbsPathBuf<S>> for AbsPath {
     #[inline]
-    fn eq(&self, other: &AbsPathBuf) -> bool {
+    fn eq(&self, other: &GenericAbsPathBuf<S>) -> bool {
         self.as_str() == other.as_str()
     }
 }
@@ -525,11 +706,11 @@ impl PartialEq<AbsPath> for str {
 }
 
 impl<'a> TryFrom<&'a str> for &'a AbsPath {
-    type Error = AbsPathNotAbsoluteError;
+    type Error = InvalidAbsPathError;
 
     #[inline]
     fn try_from(str: &'a str) -> Result<Self, Self::Error> {
-        <AbsPath>::from_str(str)
+        scan::abs_path_from_str(str)
     }
 }
 
@@ -539,8 +720,12 @@ impl<'a> TryFrom<&'a std::path::Path> for &'a AbsPath {
 
     #[inline]
     fn try_from(path: &'a std::path::Path) -> Result<Self, Self::Error> {
-        path.to_str().ok_or(AbsPathFromPathError::NotUtf8).and_then(|s| {
-            Self::try_from(s).map_err(|_| AbsPathFromPathError::NotAbsolute)
+        let str = path.to_str().ok_or(AbsPathFromPathError::NotUtf8)?;
+        Self::try_from(str).map_err(|err| match err {
+            InvalidAbsPathError::NotAbsolute => {
+                AbsPathFromPathError::NotAbsolute
+            },
+            err => AbsPathFromPathError::Invalid(err),
         })
     }
 }
@@ -550,20 +735,7 @@ impl<'path> Iterator for Components<'path> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        debug_assert!(self.inner.starts_with(MAIN_SEPARATOR_STR));
-        debug_assert!(
-            !self.inner.ends_with(MAIN_SEPARATOR_STR)
-                || self.inner == MAIN_SEPARATOR_STR
-        );
-        let s = &self.inner[1..];
-        let separator = MAIN_SEPARATOR_CHAR as u8;
-        let (component, rest) = match s.bytes().position(|b| b == separator) {
-            Some(len) => s.split_at(len),
-            None if !s.is_empty() => (s, MAIN_SEPARATOR_STR),
-            None => return None,
-        };
-        self.inner = rest;
-        Some(unsafe { NodeName::from_str_unchecked(component) })
+        self.next_const()
     }
 }
 
@@ -586,7 +758,8 @@ impl fmt::Display for AbsPathFromPathError {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::NotAbsolute => AbsPathNotAbsoluteError.fmt(f),
+            Self::NotAbsolute => InvalidAbsPathError::NotAbsolute.fmt(f),
+            Self::Invalid(err) => err.fmt(f),
             Self::NotUtf8 => f.write_str("path is not valid unicode"),
         }
     }
@@ -595,14 +768,44 @@ impl fmt::Display for AbsPathFromPathError {
 #[cfg(feature = "std")]
 impl Error for AbsPathFromPathError {}
 
-impl fmt::Display for AbsPathNotAbsoluteError {
+impl fmt::Display for InvalidAbsPathError {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str("path is not absolute")
+        match self {
+            Self::NotAbsolute => f.write_str("path is not absolute"),
+            Self::EmptyComponent { byte_offset, component_idx } => write!(
+                f,
+                "path component {component_idx} at byte offset {byte_offset} \
+                 is empty"
+            ),
+            Self::SingleDotComponent { byte_offset, component_idx } => {
+                write!(
+                    f,
+                    "path component {component_idx} at byte offset \
+                     {byte_offset} is a single dot (`.`)"
+                )
+            },
+            Self::DoubleDotComponent { byte_offset, component_idx } => {
+                write!(
+                    f,
+                    "path component {component_idx} at byte offset \
+                     {byte_offset} is a double dot (`..`)"
+                )
+            },
+            Self::InvalidCharacter { byte_offset, component_idx, ch } => {
+                write!(
+                    f,
+                    "path component {component_idx} contains invalid \
+                     character {ch:?} at byte range {}..{}",
+                    byte_offset,
+                    byte_offset + ch.len_utf8(),
+                )
+            },
+        }
     }
 }
 
-impl Error for AbsPathNotAbsoluteError {}
+impl Error for InvalidAbsPathError {}
 
 impl fmt::Display for NormalizeError {
     #[inline]
@@ -620,7 +823,7 @@ impl fmt::Display for NormalizeError {
                     byte_offset + ch.len_utf8(),
                 )
             },
-            Self::NotAbsolute => AbsPathNotAbsoluteError.fmt(f),
+            Self::NotAbsolute => InvalidAbsPathError::NotAbsolute.fmt(f),
         }
     }
 }
@@ -629,7 +832,10 @@ impl Error for NormalizeError {}
 
 #[cfg(feature = "serde")]
 mod serde_impls {
-    use serde::de::{Deserialize, Deserializer, Error};
+    use alloc::string::String;
+    use core::fmt;
+
+    use serde::de::{Deserialize, Deserializer, Error, Visitor};
     use serde::ser::{Serialize, Serializer};
 
     use super::AbsPath;
@@ -650,9 +856,75 @@ mod serde_impls {
         where
             D: Deserializer<'de>,
         {
-            <&'de str>::deserialize(deserializer)?
-                .try_into()
-                .map_err(D::Error::custom)
+            struct BorrowedVisitor;
+
+            impl<'de> Visitor<'de> for BorrowedVisitor {
+                type Value = &'de AbsPath;
+
+                #[inline]
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a borrowed absolute path")
+                }
+
+                #[inline]
+                fn visit_borrowed_str<E: Error>(
+                    self,
+                    str: &'de str,
+                ) -> Result<Self::Value, E> {
+                    str.try_into().map_err(E::custom)
+                }
+
+                #[inline]
+                fn visit_str<E: Error>(
+                    self,
+                    _: &str,
+                ) -> Result<Self::Value, E> {
+                    Err(E::custom(
+                        "can't borrow the path from the input, which is \
+                         either escaped or not kept in memory; deserialize \
+                         into an AbsPathBuf, or into a Cow<AbsPath> with \
+                         `#[serde(borrow, with = \
+                         \"abs_path::serde_as::cow\")]` instead",
+                    ))
+                }
+
+                #[inline]
+                fn visit_string<E: Error>(
+                    self,
+                    str: String,
+                ) -> Result<Self::Value, E> {
+                    self.visit_str(&str)
+                }
+            }
+
+            deserializer.deserialize_str(BorrowedVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+mod schemars_impls {
+    use alloc::borrow::Cow;
+
+    use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+    use super::AbsPath;
+    use crate::schema;
+
+    impl JsonSchema for AbsPath {
+        #[inline]
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("AbsPath")
+        }
+
+        #[inline]
+        fn schema_id() -> Cow<'static, str> {
+            schema::schema_id("AbsPath")
+        }
+
+        #[inline]
+        fn json_schema(_: &mut SchemaGenerator) -> Schema {
+            schema::abs_path_schema()
         }
     }
 }
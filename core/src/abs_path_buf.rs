@@ -1,18 +1,66 @@
-use alloc::borrow::{Borrow, ToOwned};
+use alloc::borrow::Borrow;
+use alloc::string::String;
+use core::hash::{Hash, Hasher};
 use core::ops::Deref;
 use core::{fmt, str};
 
 use compact_str::CompactString;
 
-use crate::{AbsPath, AbsPathNotAbsoluteError, MAIN_SEPARATOR_STR, NodeName};
+use crate::{
+    AbsPath,
+    InvalidAbsPathError,
+    MAIN_SEPARATOR_STR,
+    NodeName,
+    PathStorage,
+    scan,
+};
 
 /// TODO: docs.
-#[derive(Clone, PartialEq, Eq, Hash)]
-pub struct AbsPathBuf {
-    inner: CompactString,
+///
+/// This is a [`GenericAbsPathBuf`] stored in a [`CompactString`], which keeps
+/// short paths inline.
+pub type AbsPathBuf = GenericAbsPathBuf<CompactString>;
+
+/// An owned absolute path stored in any [`PathStorage`].
+///
+/// Most code should use [`AbsPathBuf`], which fixes the storage to a
+/// [`CompactString`]. This type is for callers that want a different
+/// representation, e.g. `GenericAbsPathBuf<Arc<str>>` for cheap clones.
+#[derive(Clone)]
+pub struct GenericAbsPathBuf<S> {
+    inner: S,
+}
+
+/// The archived form of an [`AbsPathBuf`], which derefs to an [`AbsPath`]
+/// without having to be deserialized.
+///
+/// Validating it with `bytecheck` checks the same invariants as
+/// [`AbsPath::from_str`].
+#[cfg(feature = "rkyv")]
+#[repr(transparent)]
+pub struct ArchivedAbsPathBuf {
+    inner: rkyv::string::ArchivedString,
 }
 
 impl AbsPathBuf {
+    /// Creates an owned path from a `'static` one without allocating.
+    ///
+    /// Short paths are stored inline and longer ones keep a reference to the
+    /// static string, so this can be used to initialize `const`s and
+    /// `static`s.
+    #[inline]
+    pub const fn from_static(path: &'static AbsPath) -> Self {
+        Self { inner: CompactString::const_new(path.as_str()) }
+    }
+
+    /// TODO: docs.
+    #[inline]
+    pub const fn root() -> Self {
+        AbsPathBuf { inner: CompactString::const_new(MAIN_SEPARATOR_STR) }
+    }
+}
+
+impl<S: PathStorage> GenericAbsPathBuf<S> {
     /// TODO: docs.
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -35,6 +83,19 @@ impl AbsPathBuf {
         self
     }
 
+    /// Creates a path from a storage holding a valid absolute path.
+    #[inline]
+    pub fn from_storage(storage: S) -> Result<Self, InvalidAbsPathError> {
+        scan::abs_path_from_str(storage.as_str())?;
+        Ok(Self { inner: storage })
+    }
+
+    /// Returns the storage holding the path.
+    #[inline]
+    pub fn into_storage(self) -> S {
+        self.inner
+    }
+
     /// TODO: docs.
     #[inline]
     pub fn join(mut self, node_name: &NodeName) -> Self {
@@ -64,19 +125,22 @@ impl AbsPathBuf {
         self
     }
 
-    /// TODO: docs.
+    /// Returns the root path stored in `S`.
+    ///
+    /// This is the same as [`root()`](AbsPathBuf::root), which only exists
+    /// for the default storage, but for any [`PathStorage`].
     #[inline]
-    pub const fn root() -> Self {
-        AbsPathBuf { inner: CompactString::const_new(MAIN_SEPARATOR_STR) }
+    pub fn root_in() -> Self {
+        Self { inner: S::from_str(MAIN_SEPARATOR_STR) }
     }
 
     #[inline]
-    pub(crate) fn new(inner: CompactString) -> Self {
+    pub(crate) fn new(inner: S) -> Self {
         Self { inner }
     }
 }
 
-impl Deref for AbsPathBuf {
+impl<S: PathStorage> Deref for GenericAbsPathBuf<S> {
     type Target = AbsPath;
 
     #[inline]
@@ -86,28 +150,28 @@ impl Deref for AbsPathBuf {
     }
 }
 
-impl Borrow<AbsPath> for AbsPathBuf {
+impl<S: PathStorage> Borrow<AbsPath> for GenericAbsPathBuf<S> {
     #[inline]
     fn borrow(&self) -> &AbsPath {
         self
     }
 }
 
-impl Borrow<str> for AbsPathBuf {
+impl<S: PathStorage> Borrow<str> for GenericAbsPathBuf<S> {
     #[inline]
     fn borrow(&self) -> &str {
         self.as_str()
     }
 }
 
-impl AsRef<AbsPath> for AbsPathBuf {
+impl<S: PathStorage> AsRef<AbsPath> for GenericAbsPathBuf<S> {
     #[inline]
     fn as_ref(&self) -> &AbsPath {
         self
     }
 }
 
-impl AsRef<str> for AbsPathBuf {
+impl<S: PathStorage> AsRef<str> for GenericAbsPathBuf<S> {
     #[inline]
     fn as_ref(&self) -> &str {
         self.as_str()
@@ -115,20 +179,20 @@ impl AsRef<str> for AbsPathBuf {
 }
 
 #[cfg(feature = "std")]
-impl AsRef<std::path::Path> for AbsPathBuf {
+impl<S: PathStorage> AsRef<std::path::Path> for GenericAbsPathBuf<S> {
     #[inline]
     fn as_ref(&self) -> &std::path::Path {
         <AbsPath>::as_ref(self)
     }
 }
 
-impl<'a> FromIterator<&'a NodeName> for AbsPathBuf {
+impl<'a, S: PathStorage> FromIterator<&'a NodeName> for GenericAbsPathBuf<S> {
     #[inline]
     fn from_iter<I>(iter: I) -> Self
     where
         I: IntoIterator<Item = &'a NodeName>,
     {
-        let mut ret = Self::root();
+        let mut ret = Self::root_in();
         for component in iter {
             ret.push(component);
         }
@@ -136,73 +200,92 @@ impl<'a> FromIterator<&'a NodeName> for AbsPathBuf {
     }
 }
 
-impl fmt::Debug for AbsPathBuf {
+impl<S: PathStorage> Hash for GenericAbsPathBuf<S> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Must be consistent with `AbsPath`'s impl for `Borrow` to work.
+        self.deref().hash(state);
+    }
+}
+
+impl<S: PathStorage> fmt::Debug for GenericAbsPathBuf<S> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("AbsPathBuf").field(&self.as_str()).finish()
     }
 }
 
-impl fmt::Display for AbsPathBuf {
+impl<S: PathStorage> fmt::Display for GenericAbsPathBuf<S> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Display::fmt(self.as_str(), f)
     }
 }
 
-impl str::FromStr for AbsPathBuf {
-    type Err = AbsPathNotAbsoluteError;
+impl<S: PathStorage> str::FromStr for GenericAbsPathBuf<S> {
+    type Err = InvalidAbsPathError;
 
     #[inline]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        <&AbsPath>::try_from(s).map(ToOwned::to_owned)
+        <&AbsPath>::try_from(s).map(Self::from)
+    }
+}
+
+impl<S: PathStorage, T: PathStorage> PartialEq<GenericAbsPathBuf<T>>
+    for GenericAbsPathBuf<S>
+{
+    #[inline]
+    fn eq(&self, other: &GenericAbsPathBuf<T>) -> bool {
+        self.as_str() == other.as_str()
     }
 }
 
-impl PartialEq<&str> for AbsPathBuf {
+impl<S: PathStorage> Eq for GenericAbsPathBuf<S> {}
+
+impl<S: PathStorage> PartialEq<&str> for GenericAbsPathBuf<S> {
     #[inline]
     fn eq(&self, other: &&str) -> bool {
         self == *other
     }
 }
 
-impl PartialEq<str> for AbsPathBuf {
+impl<S: PathStorage> PartialEq<str> for GenericAbsPathBuf<S> {
     #[inline]
     fn eq(&self, other: &str) -> bool {
         self.as_str() == other
     }
 }
 
-impl PartialEq<AbsPathBuf> for str {
+impl<S: PathStorage> PartialEq<GenericAbsPathBuf<S>> for str {
     #[inline]
-    fn eq(&self, other: &AbsPathBuf) -> bool {
+    fn eq(&self, other: &GenericAbsPathBuf<S>) -> bool {
         self == other.as_str()
     }
 }
 
-impl PartialEq<&AbsPath> for AbsPathBuf {
+impl<S: PathStorage> PartialEq<&AbsPath> for GenericAbsPathBuf<S> {
     #[inline]
     fn eq(&self, other: &&AbsPath) -> bool {
         self == *other
     }
 }
 
-impl PartialEq<AbsPath> for AbsPathBuf {
+impl<S: PathStorage> PartialEq<AbsPath> for GenericAbsPathBuf<S> {
     #[inline]
     fn eq(&self, other: &AbsPath) -> bool {
         self.deref() == other
     }
 }
 
-impl From<&AbsPath> for AbsPathBuf {
+impl<S: PathStorage> From<&AbsPath> for GenericAbsPathBuf<S> {
     #[inline]
     fn from(path: &AbsPath) -> Self {
-        path.to_owned()
+        Self::new(S::from_str(path.as_str()))
     }
 }
 
-impl TryFrom<&str> for AbsPathBuf {
-    type Error = AbsPathNotAbsoluteError;
+impl<S: PathStorage> TryFrom<&str> for GenericAbsPathBuf<S> {
+    type Error = InvalidAbsPathError;
 
     #[inline]
     fn try_from(path: &str) -> Result<Self, Self::Error> {
@@ -210,8 +293,18 @@ impl TryFrom<&str> for AbsPathBuf {
     }
 }
 
+impl<S: PathStorage> TryFrom<String> for GenericAbsPathBuf<S> {
+    type Error = InvalidAbsPathError;
+
+    #[inline]
+    fn try_from(path: String) -> Result<Self, Self::Error> {
+        scan::abs_path_from_str(&path)?;
+        Ok(Self::new(S::from_string(path)))
+    }
+}
+
 #[cfg(feature = "std")]
-impl TryFrom<&std::path::Path> for AbsPathBuf {
+impl<S: PathStorage> TryFrom<&std::path::Path> for GenericAbsPathBuf<S> {
     type Error = crate::AbsPathFromPathError;
 
     #[inline]
@@ -221,7 +314,7 @@ impl TryFrom<&std::path::Path> for AbsPathBuf {
 }
 
 #[cfg(feature = "std")]
-impl TryFrom<std::path::PathBuf> for AbsPathBuf {
+impl<S: PathStorage> TryFrom<std::path::PathBuf> for GenericAbsPathBuf<S> {
     type Error = crate::AbsPathFromPathError;
 
     #[inline]
@@ -231,48 +324,273 @@ impl TryFrom<std::path::PathBuf> for AbsPathBuf {
 }
 
 #[cfg(feature = "std")]
-impl From<AbsPathBuf> for std::path::PathBuf {
+impl<S: PathStorage> From<GenericAbsPathBuf<S>> for std::path::PathBuf {
     #[inline]
-    fn from(path: AbsPathBuf) -> Self {
+    fn from(path: GenericAbsPathBuf<S>) -> Self {
         Self::from(std::ffi::OsString::from(path))
     }
 }
 
 #[cfg(feature = "std")]
-impl From<AbsPathBuf> for std::ffi::OsString {
+impl<S: PathStorage> From<GenericAbsPathBuf<S>> for std::ffi::OsString {
     #[inline]
-    fn from(path: AbsPathBuf) -> Self {
-        path.inner.into()
+    fn from(path: GenericAbsPathBuf<S>) -> Self {
+        Self::from(path.inner.into_string())
     }
 }
 
 #[cfg(feature = "serde")]
 mod serde_impls {
-    use compact_str::CompactString;
-    use serde::de::{Deserialize, Deserializer, Error};
+    use alloc::string::String;
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    use serde::de::{Deserialize, Deserializer, Error, Visitor};
     use serde::ser::{Serialize, Serializer};
 
-    use super::AbsPathBuf;
+    use super::GenericAbsPathBuf;
+    use crate::{PathStorage, scan};
 
-    impl Serialize for AbsPathBuf {
+    impl<S: PathStorage> Serialize for GenericAbsPathBuf<S> {
         #[inline]
-        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        fn serialize<Ser>(
+            &self,
+            serializer: Ser,
+        ) -> Result<Ser::Ok, Ser::Error>
         where
-            S: Serializer,
+            Ser: Serializer,
         {
-            self.inner.serialize(serializer)
+            self.as_str().serialize(serializer)
         }
     }
 
-    impl<'de> Deserialize<'de> for AbsPathBuf {
+    /// Builds the storage straight from the deserialized string, so that
+    /// e.g. short paths stay inline in a [`CompactString`] without going
+    /// through a temporary [`String`].
+    ///
+    /// [`CompactString`]: compact_str::CompactString
+    struct AbsPathBufVisitor<S>(PhantomData<S>);
+
+    impl<'de, S: PathStorage> Deserialize<'de> for GenericAbsPathBuf<S> {
         #[inline]
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
             D: Deserializer<'de>,
         {
-            CompactString::deserialize(deserializer)?
-                .parse()
-                .map_err(D::Error::custom)
+            deserializer.deserialize_str(AbsPathBufVisitor(PhantomData))
+        }
+    }
+
+    impl<S: PathStorage> Visitor<'_> for AbsPathBufVisitor<S> {
+        type Value = GenericAbsPathBuf<S>;
+
+        #[inline]
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an absolute path")
+        }
+
+        #[inline]
+        fn visit_str<E: Error>(self, str: &str) -> Result<Self::Value, E> {
+            scan::abs_path_from_str(str).map_err(E::custom)?;
+            Ok(GenericAbsPathBuf::new(S::from_str(str)))
+        }
+
+        #[inline]
+        fn visit_string<E: Error>(
+            self,
+            string: String,
+        ) -> Result<Self::Value, E> {
+            scan::abs_path_from_str(&string).map_err(E::custom)?;
+            Ok(GenericAbsPathBuf::new(S::from_string(string)))
+        }
+    }
+}
+
+#[cfg(feature = "rkyv")]
+mod rkyv_impls {
+    use core::hash::{Hash, Hasher};
+    use core::ops::Deref;
+    use core::{fmt, ptr};
+
+    use rkyv::bytecheck::CheckBytes;
+    use rkyv::rancor::{Fallible, Source};
+    use rkyv::string::{ArchivedString, StringResolver};
+    use rkyv::{
+        Archive,
+        Deserialize,
+        Place,
+        Portable,
+        Serialize,
+        SerializeUnsized,
+    };
+
+    use super::{ArchivedAbsPathBuf, GenericAbsPathBuf};
+    use crate::{AbsPath, PathStorage, scan};
+
+    impl<S: PathStorage> Archive for GenericAbsPathBuf<S> {
+        type Archived = ArchivedAbsPathBuf;
+        type Resolver = StringResolver;
+
+        #[inline]
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            // SAFETY: `ArchivedAbsPathBuf` is a transparent wrapper around an
+            // `ArchivedString`.
+            let out = unsafe { out.cast_unchecked::<ArchivedString>() };
+            ArchivedString::resolve_from_str(self.as_str(), resolver, out);
+        }
+    }
+
+    impl<S, Ser> Serialize<Ser> for GenericAbsPathBuf<S>
+    where
+        S: PathStorage,
+        Ser: Fallible + ?Sized,
+        Ser::Error: Source,
+        str: SerializeUnsized<Ser>,
+    {
+        #[inline]
+        fn serialize(
+            &self,
+            serializer: &mut Ser,
+        ) -> Result<Self::Resolver, Ser::Error> {
+            ArchivedString::serialize_from_str(self.as_str(), serializer)
+        }
+    }
+
+    impl<S, D> Deserialize<GenericAbsPathBuf<S>, D> for ArchivedAbsPathBuf
+    where
+        S: PathStorage,
+        D: Fallible + ?Sized,
+    {
+        #[inline]
+        fn deserialize(
+            &self,
+            _: &mut D,
+        ) -> Result<GenericAbsPathBuf<S>, D::Error> {
+            Ok(GenericAbsPathBuf::from(&**self))
+        }
+    }
+
+    // SAFETY: `ArchivedString` is `Portable`, and this is a transparent
+    // wrapper around it.
+    unsafe impl Portable for ArchivedAbsPathBuf {}
+
+    // SAFETY: the string is checked first, and then validated as a path.
+    unsafe impl<C> CheckBytes<C> for ArchivedAbsPathBuf
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+        ArchivedString: CheckBytes<C>,
+    {
+        #[inline]
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            let inner = unsafe { ptr::addr_of!((*value).inner) };
+            unsafe { ArchivedString::check_bytes(inner, context)? };
+            let str = unsafe { &*inner }.as_str();
+            scan::abs_path_from_str(str).map_err(C::Error::new)?;
+            Ok(())
+        }
+    }
+
+    impl Deref for ArchivedAbsPathBuf {
+        type Target = AbsPath;
+
+        #[inline]
+        fn deref(&self) -> &Self::Target {
+            // SAFETY: the string was either validated by `check_bytes` or
+            // archived from a valid path.
+            unsafe { AbsPath::from_str_unchecked(self.inner.as_str()) }
+        }
+    }
+
+    impl AsRef<AbsPath> for ArchivedAbsPathBuf {
+        #[inline]
+        fn as_ref(&self) -> &AbsPath {
+            self
+        }
+    }
+
+    impl fmt::Debug for ArchivedAbsPathBuf {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_tuple("ArchivedAbsPathBuf").field(&self.as_str()).finish()
+        }
+    }
+
+    impl fmt::Display for ArchivedAbsPathBuf {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Display::fmt(&**self, f)
+        }
+    }
+
+    impl Hash for ArchivedAbsPathBuf {
+        #[inline]
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.deref().hash(state);
+        }
+    }
+
+    impl PartialEq for ArchivedAbsPathBuf {
+        #[inline]
+        fn eq(&self, other: &Self) -> bool {
+            **self == **other
+        }
+    }
+
+    impl Eq for ArchivedAbsPathBuf {}
+
+    impl PartialEq<AbsPath> for ArchivedAbsPathBuf {
+        #[inline]
+        fn eq(&self, other: &AbsPath) -> bool {
+            &**self == other
+        }
+    }
+
+    impl<S: PathStorage> PartialEq<GenericAbsPathBuf<S>> for ArchivedAbsPathBuf {
+        #[inline]
+        fn eq(&self, other: &GenericAbsPathBuf<S>) -> bool {
+            **self == **other
+        }
+    }
+
+    impl PartialEq<str> for ArchivedAbsPathBuf {
+        #[inline]
+        fn eq(&self, other: &str) -> bool {
+            self.as_str() == other
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+mod schemars_impls {
+    use alloc::borrow::Cow;
+
+    use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+    use super::GenericAbsPathBuf;
+    use crate::{PathStorage, schema};
+
+    impl<S: PathStorage> JsonSchema for GenericAbsPathBuf<S> {
+        #[inline]
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("AbsPath")
+        }
+
+        #[inline]
+        fn schema_id() -> Cow<'static, str> {
+            schema::schema_id("AbsPath")
+        }
+
+        #[inline]
+        fn json_schema(_: &mut SchemaGenerator) -> Schema {
+            schema::abs_path_schema()
         }
     }
 }
@@ -0,0 +1,91 @@
+//! Generators for the [`arbitrary`] crate, for fuzzing code against the real
+//! invariants of the path types.
+//!
+//! The [`Arbitrary`] impls use the [default](GenerateOptions::default)
+//! options. Use the functions in this module to customize them.
+
+use alloc::string::String;
+
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::generate::{self, GenerateOptions};
+use crate::{GenericAbsPathBuf, MAIN_SEPARATOR_STR, NodeNameBuf, PathStorage};
+
+/// Generates a valid node name.
+#[inline]
+pub fn node_name_buf(
+    u: &mut Unstructured,
+    options: &GenerateOptions,
+) -> Result<NodeNameBuf> {
+    let len = u.int_in_range(1..=options.get_max_name_len())?;
+    let mut name = String::with_capacity(len);
+
+    for _ in 0..len {
+        let ch = match options.get_alphabet().chars() {
+            Some(chars) => *u.choose(chars)?,
+            None => Some(char::arbitrary(u)?)
+                .filter(|&ch| generate::is_valid_char(ch))
+                .unwrap_or('_'),
+        };
+        name.push(ch);
+    }
+
+    generate::escape_dots(&mut name);
+    Ok(NodeNameBuf::new(name.into()))
+}
+
+/// Generates a valid absolute path.
+#[inline]
+pub fn abs_path_buf<S: PathStorage>(
+    u: &mut Unstructured,
+    options: &GenerateOptions,
+) -> Result<GenericAbsPathBuf<S>> {
+    let depth = u.int_in_range(0..=options.get_max_depth())?;
+    let mut path = GenericAbsPathBuf::root_in();
+    for _ in 0..depth {
+        path.push(node_name_buf(u, options)?);
+    }
+    Ok(path)
+}
+
+/// Generates a string that starts with a separator, but whose components
+/// can also be empty, `.` or `..`, like the inputs of
+/// [`AbsPath::normalize`](crate::AbsPath::normalize).
+#[inline]
+pub fn almost_valid_path(
+    u: &mut Unstructured,
+    options: &GenerateOptions,
+) -> Result<String> {
+    let depth = u.int_in_range(0..=options.get_max_depth())?;
+    let mut path = String::new();
+
+    for _ in 0..depth {
+        path.push_str(MAIN_SEPARATOR_STR);
+        match u.int_in_range(0..=5u8)? {
+            0..=2 => path.push_str(node_name_buf(u, options)?.as_str()),
+            3 => path.push('.'),
+            4 => path.push_str(".."),
+            _ => {},
+        }
+    }
+
+    if path.is_empty() || u.ratio(1, 4)? {
+        path.push_str(MAIN_SEPARATOR_STR);
+    }
+
+    Ok(path)
+}
+
+impl<'a> Arbitrary<'a> for NodeNameBuf {
+    #[inline]
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        node_name_buf(u, &GenerateOptions::default())
+    }
+}
+
+impl<'a, S: PathStorage> Arbitrary<'a> for GenericAbsPathBuf<S> {
+    #[inline]
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        abs_path_buf(u, &GenerateOptions::default())
+    }
+}
@@ -0,0 +1,134 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use crate::{AbsPath, NodeName};
+#[cfg(feature = "alloc")]
+use crate::{AbsPathBuf, NodeNameBuf};
+
+/// A wrapper around a path or a file name whose [`Eq`], [`Hash`] and [`Ord`]
+/// impls ignore differences in case.
+///
+/// Characters are compared after applying Unicode simple case folding (i.e.
+/// the one-to-one mappings without the Turkic special cases), which is
+/// close to what case-insensitive file systems like APFS and NTFS do. This
+/// means that `/Src/Main.rs` and `/src/main.rs` compare equal and hash to
+/// the same value, but `Straße` and `STRASSE` don't.
+#[derive(Copy, Clone)]
+pub struct CaseInsensitive<T>(pub T);
+
+impl<'path> CaseInsensitive<&'path AbsPath> {
+    /// Same as [`AbsPath::starts_with`], but ignoring case.
+    #[inline]
+    pub fn starts_with(&self, base: &AbsPath) -> bool {
+        self.strip_prefix(base).is_some()
+    }
+
+    /// Same as [`AbsPath::strip_prefix`], but ignoring case.
+    ///
+    /// The returned path is a subslice of the wrapped path, so it keeps its
+    /// original case.
+    #[inline]
+    pub fn strip_prefix(&self, base: &AbsPath) -> Option<&'path AbsPath> {
+        let mut components = self.0.components();
+        for base_component in base.components() {
+            let component = components.next()?;
+            if !eq_ignore_case(component, base_component) {
+                return None;
+            }
+        }
+        Some(components.as_path())
+    }
+}
+
+/// Folds the case of the given character.
+#[inline]
+fn fold_char(ch: char) -> char {
+    if ch.is_ascii() {
+        return ch.to_ascii_lowercase();
+    }
+
+    // The dotless i is the only character that's not in the same case
+    // folding class as its uppercase form (`I`).
+    if ch == 'ı' {
+        return ch;
+    }
+
+    let upper = single_char(ch.to_uppercase()).unwrap_or(ch);
+    single_char(upper.to_lowercase()).unwrap_or(upper)
+}
+
+#[inline]
+fn fold_str(str: &str) -> impl Iterator<Item = char> + '_ {
+    str.chars().map(fold_char)
+}
+
+#[inline]
+fn eq_ignore_case(lhs: &str, rhs: &str) -> bool {
+    fold_str(lhs).eq(fold_str(rhs))
+}
+
+#[inline]
+fn single_char(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    let ch = chars.next()?;
+    chars.next().is_none().then_some(ch)
+}
+
+macro_rules! impl_case_insensitive {
+    ([$($lt:lifetime)?] $ty:ty) => {
+        impl<$($lt)?> PartialEq for CaseInsensitive<$ty> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                eq_ignore_case(self.0.as_str(), other.0.as_str())
+            }
+        }
+
+        impl<$($lt)?> Eq for CaseInsensitive<$ty> {}
+
+        impl<$($lt)?> PartialOrd for CaseInsensitive<$ty> {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<$($lt)?> Ord for CaseInsensitive<$ty> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                fold_str(self.0.as_str()).cmp(fold_str(other.0.as_str()))
+            }
+        }
+
+        impl<$($lt)?> Hash for CaseInsensitive<$ty> {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                for ch in fold_str(self.0.as_str()) {
+                    state.write_u32(ch as u32);
+                }
+                // Same as `str`'s impl, to make the encoding prefix-free.
+                state.write_u8(0xff);
+            }
+        }
+
+        impl<$($lt)?> fmt::Debug for CaseInsensitive<$ty> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_tuple("CaseInsensitive").field(&self.0).finish()
+            }
+        }
+
+        impl<$($lt)?> fmt::Display for CaseInsensitive<$ty> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+    };
+}
+
+impl_case_insensitive!(['a] &'a AbsPath);
+#[cfg(feature = "alloc")]
+impl_case_insensitive!([] AbsPathBuf);
+impl_case_insensitive!(['a] &'a NodeName);
+#[cfg(feature = "alloc")]
+impl_case_insensitive!([] NodeNameBuf);
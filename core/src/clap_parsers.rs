@@ -0,0 +1,216 @@
+//! [`clap`] value parsers for the path types.
+//!
+//! [`AbsPathBuf`] and [`NodeNameBuf`] implement
+//! [`ValueParserFactory`], so `#[arg(value_parser)]` picks the parsers in
+//! this module automatically. Use [`AbsPathBufValueParser`] directly to also
+//! check that the path exists.
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::io;
+
+use ::clap::builder::{TypedValueParser, ValueParserFactory};
+use ::clap::{Arg, Command, Error};
+
+use crate::{
+    AbsPath,
+    AbsPathBuf,
+    MAIN_SEPARATOR_CHAR,
+    MAIN_SEPARATOR_STR,
+    NodeName,
+    NodeNameBuf,
+    NormalizeError,
+};
+
+/// Parses an argument into an [`AbsPathBuf`], resolving relative paths
+/// against the current directory and [normalizing](AbsPath::normalize) the
+/// result.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AbsPathBufValueParser {
+    must_exist: Option<Existence>,
+}
+
+/// Parses an argument into a [`NodeNameBuf`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NodeNameBufValueParser;
+
+#[derive(Copy, Clone, Debug)]
+enum Existence {
+    Any,
+    Dir,
+    File,
+}
+
+impl AbsPathBufValueParser {
+    /// Requires the path to exist and be a directory.
+    #[inline]
+    pub fn must_be_dir(mut self) -> Self {
+        self.must_exist = Some(Existence::Dir);
+        self
+    }
+
+    /// Requires the path to exist and be a file.
+    #[inline]
+    pub fn must_be_file(mut self) -> Self {
+        self.must_exist = Some(Existence::File);
+        self
+    }
+
+    /// Requires the path to exist.
+    #[inline]
+    pub fn must_exist(mut self) -> Self {
+        self.must_exist = Some(Existence::Any);
+        self
+    }
+
+    /// Creates a new parser that accepts paths that don't exist.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    fn parse(self, input: &str) -> Result<AbsPathBuf, String> {
+        let (joined, prefix_len) = if input.starts_with(MAIN_SEPARATOR_STR) {
+            (Cow::Borrowed(input), 0)
+        } else {
+            let current_dir = std::env::current_dir().map_err(|err| {
+                format!("couldn't get the current directory: {err}")
+            })?;
+            let current_dir = current_dir
+                .to_str()
+                .ok_or("the current directory is not valid UTF-8")?;
+            let joined = [current_dir, MAIN_SEPARATOR_STR, input].concat();
+            (Cow::Owned(joined), current_dir.len() + 1)
+        };
+
+        let path = AbsPath::normalize(&joined)
+            .map_err(|err| describe_normalize_error(err, &joined, prefix_len))?
+            .into_owned();
+
+        let Some(must_exist) = self.must_exist else { return Ok(path) };
+
+        let metadata = std::fs::metadata(&path).map_err(|err| {
+            if err.kind() == io::ErrorKind::NotFound {
+                format!("'{path}' does not exist")
+            } else {
+                format!("couldn't access '{path}': {err}")
+            }
+        })?;
+
+        match must_exist {
+            Existence::Dir if !metadata.is_dir() => {
+                Err(format!("'{path}' is not a directory"))
+            },
+            Existence::File if !metadata.is_file() => {
+                Err(format!("'{path}' is not a file"))
+            },
+            _ => Ok(path),
+        }
+    }
+}
+
+impl NodeNameBufValueParser {
+    /// Creates a new parser.
+    #[inline]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Turns the error into a message that quotes the offending component.
+///
+/// `prefix_len` is the length of the current directory prepended to the
+/// argument, which isn't counted in the reported byte offsets.
+#[inline]
+fn describe_normalize_error(
+    err: NormalizeError,
+    path: &str,
+    prefix_len: usize,
+) -> String {
+    match err {
+        NormalizeError::InvalidCharacter { byte_offset, ch } => {
+            let start = path[..byte_offset]
+                .rfind(MAIN_SEPARATOR_CHAR)
+                .map_or(0, |idx| idx + 1);
+            let end = path[byte_offset..]
+                .find(MAIN_SEPARATOR_CHAR)
+                .map_or(path.len(), |idx| byte_offset + idx);
+            format!(
+                "component '{}' contains the invalid character {ch:?}",
+                &path[start..end]
+            )
+        },
+        NormalizeError::EscapesRoot => {
+            let mut depth = 0usize;
+            let mut offset = 0usize;
+            for component in path.split(MAIN_SEPARATOR_CHAR) {
+                match component {
+                    "" | "." => {},
+                    ".." if depth == 0 => {
+                        return format!(
+                            "component '..' at byte {} navigates above the \
+                             root",
+                            offset.saturating_sub(prefix_len)
+                        );
+                    },
+                    ".." => depth -= 1,
+                    _ => depth += 1,
+                }
+                offset += component.len() + 1;
+            }
+            err.to_string()
+        },
+        NormalizeError::NotAbsolute => err.to_string(),
+    }
+}
+
+impl TypedValueParser for AbsPathBufValueParser {
+    type Value = AbsPathBuf;
+
+    #[inline]
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        // Going through a closure gets us clap's own formatting for
+        // validation errors, which isn't otherwise public.
+        let this = *self;
+        (move |str: &str| this.parse(str)).parse_ref(cmd, arg, value)
+    }
+}
+
+impl TypedValueParser for NodeNameBufValueParser {
+    type Value = NodeNameBuf;
+
+    #[inline]
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        (|str: &str| <&NodeName>::try_from(str).map(ToOwned::to_owned))
+            .parse_ref(cmd, arg, value)
+    }
+}
+
+impl ValueParserFactory for AbsPathBuf {
+    type Parser = AbsPathBufValueParser;
+
+    #[inline]
+    fn value_parser() -> Self::Parser {
+        AbsPathBufValueParser::new()
+    }
+}
+
+impl ValueParserFactory for NodeNameBuf {
+    type Parser = NodeNameBufValueParser;
+
+    #[inline]
+    fn value_parser() -> Self::Parser {
+        NodeNameBufValueParser::new()
+    }
+}
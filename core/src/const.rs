@@ -68,14 +68,19 @@ pub(crate) const fn str_ends_with_str(str: &str, suffix: &str) -> bool {
     }
 }
 
-/// Returns the first character of `str` that's included in `chars`, or `None`
-/// if none of them are.
-pub(crate) const fn str_find_char(str: &str, chars: &[char]) -> Option<char> {
+/// Returns the byte offset of the first character of `str` that's included in
+/// `chars` together with the character itself, or `None` if none of them are.
+pub(crate) const fn str_find_char(
+    str: &str,
+    chars: &[char],
+) -> Option<(usize, char)> {
     let mut iter = str_chars(str);
+    let mut offset = 0;
     while let Some(char) = iter.next() {
         if char_contains(char, chars) {
-            return Some(char);
+            return Some((offset, char));
         }
+        offset += char.len_utf8();
     }
     None
 }
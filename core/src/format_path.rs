@@ -0,0 +1,117 @@
+use alloc::string::String;
+use core::error::Error;
+use core::fmt::{self, Write};
+
+use crate::{AbsPathBuf, InvalidNodeNameError, NodeName, scan};
+
+/// The error type returned by the `format_path!` macro when one of the
+/// interpolated values is not a valid [`NodeName`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FormatPathError {
+    placeholder: &'static str,
+    err: InvalidNodeNameError,
+}
+
+/// Builds the path returned by the `format_path!` macro one piece at a time.
+///
+/// The first invalid value is recorded and returned by
+/// [`finish`](Self::finish), and every call after that is a no-op.
+#[doc(hidden)]
+pub struct FormatPath {
+    component: String,
+    err: Option<FormatPathError>,
+    path: AbsPathBuf,
+}
+
+impl FormatPathError {
+    /// Returns the reason why the value was rejected.
+    #[inline]
+    pub fn node_name_error(&self) -> InvalidNodeNameError {
+        self.err
+    }
+
+    /// Returns the name of the placeholder whose value was rejected.
+    #[inline]
+    pub fn placeholder(&self) -> &'static str {
+        self.placeholder
+    }
+}
+
+impl FormatPath {
+    /// Pushes the current component to the path.
+    #[inline]
+    pub fn end_component(&mut self) {
+        if self.err.is_some() {
+            return;
+        }
+        debug_assert!(NodeName::from_str(&self.component).is_ok());
+        // SAFETY: the fixed parts of the component were validated at
+        // compile time, and every interpolated value was validated by
+        // `push_value()`.
+        self.path
+            .push(unsafe { NodeName::from_str_unchecked(&self.component) });
+        self.component.clear();
+    }
+
+    /// Returns the formatted path, or the error for the first invalid value.
+    #[inline]
+    pub fn finish(self) -> Result<AbsPathBuf, FormatPathError> {
+        match self.err {
+            Some(err) => Err(err),
+            None => Ok(self.path),
+        }
+    }
+
+    /// Appends a fixed part of the template to the current component.
+    #[inline]
+    pub fn push_str(&mut self, str: &str) {
+        if self.err.is_none() {
+            self.component.push_str(str);
+        }
+    }
+
+    /// Formats the value of the given placeholder and appends it to the
+    /// current component if it's a valid node name.
+    #[inline]
+    pub fn push_value(
+        &mut self,
+        placeholder: &'static str,
+        value: fmt::Arguments,
+    ) {
+        if self.err.is_some() {
+            return;
+        }
+        let value_start = self.component.len();
+        let _ = self.component.write_fmt(value);
+        if let Err(err) =
+            scan::node_name_from_str(&self.component[value_start..])
+        {
+            self.err = Some(FormatPathError { placeholder, err });
+        }
+    }
+}
+
+impl Default for FormatPath {
+    #[inline]
+    fn default() -> Self {
+        Self { component: String::new(), err: None, path: AbsPathBuf::root() }
+    }
+}
+
+impl fmt::Display for FormatPathError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "value of placeholder `{}` is not a valid file name: {}",
+            self.placeholder, self.err
+        )
+    }
+}
+
+impl Error for FormatPathError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.err)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::node_name::INVALID_CHARACTERS;
+
+/// Options controlling the paths and names generated by the
+/// [`arbitrary_gen`](crate::arbitrary_gen) and
+/// [`proptest_gen`](crate::proptest_gen) integrations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GenerateOptions {
+    alphabet: Alphabet,
+    max_depth: usize,
+    max_name_len: usize,
+}
+
+/// The characters node names are generated from.
+///
+/// Characters that are invalid in node names on the current platform are
+/// never generated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Alphabet {
+    /// ASCII letters and digits, plus `-`, `_`, `.` and space.
+    Ascii,
+
+    /// Any Unicode scalar value.
+    Unicode,
+
+    /// Like [`Ascii`](Self::Ascii), but also including the characters that
+    /// are only invalid on other platforms, like `:` or `\`. Useful to test
+    /// code that checks for portability.
+    PlatformInvalid,
+}
+
+impl GenerateOptions {
+    /// Sets the characters node names are generated from.
+    #[inline]
+    pub const fn alphabet(mut self, alphabet: Alphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    /// Sets the maximum number of components in generated paths.
+    #[inline]
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum number of characters in generated node names, which
+    /// is clamped to at least 1.
+    #[inline]
+    pub const fn max_name_len(mut self, max_name_len: usize) -> Self {
+        self.max_name_len = if max_name_len == 0 { 1 } else { max_name_len };
+        self
+    }
+
+    #[inline]
+    pub(crate) fn get_alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    #[inline]
+    pub(crate) fn get_max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    #[inline]
+    pub(crate) fn get_max_name_len(&self) -> usize {
+        self.max_name_len
+    }
+}
+
+impl Alphabet {
+    /// Returns the characters in the alphabet, or `None` for
+    /// [`Unicode`](Self::Unicode).
+    #[inline]
+    pub(crate) fn chars(self) -> Option<&'static [char]> {
+        match self {
+            Self::Ascii => Some(ASCII),
+            Self::Unicode => None,
+            Self::PlatformInvalid => Some(PLATFORM_INVALID),
+        }
+    }
+}
+
+impl Default for GenerateOptions {
+    #[inline]
+    fn default() -> Self {
+        Self { alphabet: Alphabet::Ascii, max_depth: 8, max_name_len: 16 }
+    }
+}
+
+const ASCII: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D',
+    'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '0', '1', '2', '3', '4', '5', '6', '7',
+    '8', '9', '-', '_', '.', ' ',
+];
+
+#[cfg(not(target_os = "windows"))]
+const PLATFORM_INVALID: &[char] = &[
+    'a', 'b', 'c', 'x', 'y', 'z', 'A', 'Z', '0', '9', '-', '_', '.', ' ', '<',
+    '>', ':', '"', '\\', '|', '?', '*', '\r', '\n', '\t',
+];
+
+#[cfg(target_os = "windows")]
+const PLATFORM_INVALID: &[char] =
+    &['a', 'b', 'c', 'x', 'y', 'z', 'A', 'Z', '0', '9', '-', '_', '.', ' '];
+
+const _: () = {
+    let mut idx = 0;
+    while idx < PLATFORM_INVALID.len() {
+        let mut invalid_idx = 0;
+        while invalid_idx < INVALID_CHARACTERS.len() {
+            assert!(PLATFORM_INVALID[idx] != INVALID_CHARACTERS[invalid_idx]);
+            invalid_idx += 1;
+        }
+        idx += 1;
+    }
+};
+
+/// Returns whether the given character can appear in a node name on the
+/// current platform.
+#[inline]
+pub(crate) fn is_valid_char(ch: char) -> bool {
+    !INVALID_CHARACTERS.contains(&ch)
+}
+
+/// Turns the generated string into a valid node name by escaping `.` and
+/// `..`, which are the only invalid names made of valid characters.
+#[inline]
+pub(crate) fn escape_dots(name: &mut alloc::string::String) {
+    if name == "." || name == ".." {
+        name.push('_');
+    }
+}
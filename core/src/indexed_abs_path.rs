@@ -0,0 +1,274 @@
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+
+use crate::{AbsPath, AbsPathBuf, MAIN_SEPARATOR_CHAR, NodeName};
+
+/// A borrowed [`AbsPath`] together with the byte offsets of its separators,
+/// which allows accessing any component or ancestor in constant time.
+///
+/// The offsets are computed once when the path is created, so this is
+/// useful when the same path is accessed at random depths many times.
+#[derive(Clone)]
+pub struct IndexedAbsPath<'path> {
+    path: &'path AbsPath,
+    separator_offsets: Vec<usize>,
+}
+
+/// The owned version of [`IndexedAbsPath`], which can also be extended and
+/// shortened while keeping its offsets up to date.
+#[derive(Clone)]
+pub struct IndexedAbsPathBuf {
+    path: AbsPathBuf,
+    separator_offsets: Vec<usize>,
+}
+
+impl<'path> IndexedAbsPath<'path> {
+    /// Returns the path made of the first `depth` components, or `None` if
+    /// `depth` is greater than the path's [`depth`](Self::depth).
+    #[inline]
+    pub fn ancestor(&self, depth: usize) -> Option<&'path AbsPath> {
+        ancestor(self.path, &self.separator_offsets, depth)
+    }
+
+    /// Returns the underlying path.
+    #[inline]
+    pub fn as_path(&self) -> &'path AbsPath {
+        self.path
+    }
+
+    /// Returns the component at the given zero-based index, or `None` if the
+    /// index is out of bounds.
+    #[inline]
+    pub fn component(&self, idx: usize) -> Option<&'path NodeName> {
+        component(self.path, &self.separator_offsets, idx)
+    }
+
+    /// Returns the number of components in the path, which is zero for the
+    /// root.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.separator_offsets.len()
+    }
+
+    /// Indexes the given path.
+    #[inline]
+    pub fn new(path: &'path AbsPath) -> Self {
+        Self { path, separator_offsets: separator_offsets(path) }
+    }
+
+    /// Returns an owned copy of this path, reusing the computed offsets.
+    #[inline]
+    pub fn to_indexed_buf(&self) -> IndexedAbsPathBuf {
+        IndexedAbsPathBuf {
+            path: self.path.to_owned(),
+            separator_offsets: self.separator_offsets.clone(),
+        }
+    }
+}
+
+impl IndexedAbsPathBuf {
+    /// Returns the path made of the first `depth` components, or `None` if
+    /// `depth` is greater than the path's [`depth`](Self::depth).
+    #[inline]
+    pub fn ancestor(&self, depth: usize) -> Option<&AbsPath> {
+        ancestor(&self.path, &self.separator_offsets, depth)
+    }
+
+    /// Returns the underlying path.
+    #[inline]
+    pub fn as_path(&self) -> &AbsPath {
+        &self.path
+    }
+
+    /// Returns the component at the given zero-based index, or `None` if the
+    /// index is out of bounds.
+    #[inline]
+    pub fn component(&self, idx: usize) -> Option<&NodeName> {
+        component(&self.path, &self.separator_offsets, idx)
+    }
+
+    /// Returns the number of components in the path, which is zero for the
+    /// root.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.separator_offsets.len()
+    }
+
+    /// Returns the underlying path, dropping the offsets.
+    #[inline]
+    pub fn into_path_buf(self) -> AbsPathBuf {
+        self.path
+    }
+
+    /// Indexes the given path.
+    #[inline]
+    pub fn new(path: AbsPathBuf) -> Self {
+        let separator_offsets = separator_offsets(&path);
+        Self { path, separator_offsets }
+    }
+
+    /// Removes the last component of the path, returning `false` if the path
+    /// is the root.
+    #[inline]
+    pub fn pop(&mut self) -> bool {
+        let popped = self.path.pop();
+        if popped {
+            self.separator_offsets.pop();
+        }
+        popped
+    }
+
+    /// Appends the given node name to the path.
+    #[inline]
+    pub fn push<T: AsRef<NodeName>>(&mut self, node_name: T) -> &mut Self {
+        let separator_offset =
+            if self.path.is_root() { 0 } else { self.path.len() };
+        self.path.push(node_name);
+        self.separator_offsets.push(separator_offset);
+        self
+    }
+}
+
+/// Returns the offsets of the separators that precede each component.
+#[inline]
+fn separator_offsets(path: &AbsPath) -> Vec<usize> {
+    if path.is_root() {
+        return Vec::new();
+    }
+    path.as_str()
+        .bytes()
+        .enumerate()
+        .filter_map(|(offset, byte)| {
+            (byte == MAIN_SEPARATOR_CHAR as u8).then_some(offset)
+        })
+        .collect()
+}
+
+#[inline]
+fn ancestor<'path>(
+    path: &'path AbsPath,
+    separator_offsets: &[usize],
+    depth: usize,
+) -> Option<&'path AbsPath> {
+    let end = match depth {
+        0 => return Some(AbsPath::root()),
+        depth if depth == separator_offsets.len() => path.len(),
+        depth => *separator_offsets.get(depth)?,
+    };
+    // SAFETY: every prefix of a valid absolute path that ends right before a
+    // separator is also a valid absolute path.
+    Some(unsafe { AbsPath::from_str_unchecked(&path.as_str()[..end]) })
+}
+
+#[inline]
+fn component<'path>(
+    path: &'path AbsPath,
+    separator_offsets: &[usize],
+    idx: usize,
+) -> Option<&'path NodeName> {
+    let start = separator_offsets.get(idx)? + 1;
+    let end = separator_offsets.get(idx + 1).copied().unwrap_or(path.len());
+    // SAFETY: the bytes between two separators are a valid node name.
+    Some(unsafe { NodeName::from_str_unchecked(&path.as_str()[start..end]) })
+}
+
+impl Deref for IndexedAbsPath<'_> {
+    type Target = AbsPath;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.path
+    }
+}
+
+impl Deref for IndexedAbsPathBuf {
+    type Target = AbsPath;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.path
+    }
+}
+
+impl AsRef<AbsPath> for IndexedAbsPath<'_> {
+    #[inline]
+    fn as_ref(&self) -> &AbsPath {
+        self.path
+    }
+}
+
+impl AsRef<AbsPath> for IndexedAbsPathBuf {
+    #[inline]
+    fn as_ref(&self) -> &AbsPath {
+        &self.path
+    }
+}
+
+impl<'path> From<&'path AbsPath> for IndexedAbsPath<'path> {
+    #[inline]
+    fn from(path: &'path AbsPath) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<AbsPathBuf> for IndexedAbsPathBuf {
+    #[inline]
+    fn from(path: AbsPathBuf) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<IndexedAbsPathBuf> for AbsPathBuf {
+    #[inline]
+    fn from(path: IndexedAbsPathBuf) -> Self {
+        path.into_path_buf()
+    }
+}
+
+impl fmt::Debug for IndexedAbsPath<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IndexedAbsPath").field(&self.path.as_str()).finish()
+    }
+}
+
+impl fmt::Debug for IndexedAbsPathBuf {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IndexedAbsPathBuf").field(&self.path.as_str()).finish()
+    }
+}
+
+impl fmt::Display for IndexedAbsPath<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.path, f)
+    }
+}
+
+impl fmt::Display for IndexedAbsPathBuf {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.path, f)
+    }
+}
+
+impl PartialEq for IndexedAbsPath<'_> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl Eq for IndexedAbsPath<'_> {}
+
+impl PartialEq for IndexedAbsPathBuf {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl Eq for IndexedAbsPathBuf {}
@@ -0,0 +1,101 @@
+use core::error::Error;
+use core::{fmt, str};
+
+/// The error type returned when a fixed-capacity buffer like
+/// [`StackAbsPathBuf`](crate::StackAbsPathBuf) is too small to hold the
+/// result of an operation.
+///
+/// The buffer is left unchanged when this error is returned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CapacityError {
+    capacity: usize,
+    required_len: usize,
+}
+
+/// A string stored in a `[u8; N]` buffer, used by the fixed-capacity path
+/// and file name types.
+#[derive(Copy, Clone)]
+pub(crate) struct InlineStr<const N: usize> {
+    /// The string is stored in `buf[..len]`.
+    buf: [u8; N],
+    len: usize,
+}
+
+impl CapacityError {
+    /// Returns the capacity of the buffer, in bytes.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the length in bytes that the buffer would have needed to
+    /// hold the result.
+    #[inline]
+    pub const fn required_len(&self) -> usize {
+        self.required_len
+    }
+}
+
+impl<const N: usize> InlineStr<N> {
+    #[inline]
+    pub(crate) const fn as_str(&self) -> &str {
+        let (bytes, _) = self.buf.split_at(self.len);
+        // SAFETY: the buffer is only ever filled with whole strings.
+        unsafe { str::from_utf8_unchecked(bytes) }
+    }
+
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Self { buf: [0; N], len: 0 }
+    }
+
+    /// Appends all the given strings, or none of them if they don't fit.
+    #[inline]
+    pub(crate) const fn push_strs(
+        &mut self,
+        strs: &[&str],
+    ) -> Result<(), CapacityError> {
+        let mut required_len = self.len;
+        let mut idx = 0;
+        while idx < strs.len() {
+            required_len += strs[idx].len();
+            idx += 1;
+        }
+        if required_len > N {
+            return Err(CapacityError { capacity: N, required_len });
+        }
+        let mut idx = 0;
+        while idx < strs.len() {
+            let bytes = strs[idx].as_bytes();
+            let mut byte_idx = 0;
+            while byte_idx < bytes.len() {
+                self.buf[self.len] = bytes[byte_idx];
+                self.len += 1;
+                byte_idx += 1;
+            }
+            idx += 1;
+        }
+        Ok(())
+    }
+
+    /// Shortens the string to the given length, which must be on a char
+    /// boundary.
+    #[inline]
+    pub(crate) const fn truncate(&mut self, len: usize) {
+        debug_assert!(self.as_str().is_char_boundary(len));
+        self.len = len;
+    }
+}
+
+impl fmt::Display for CapacityError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "result is {} bytes long, but the buffer can only hold {}",
+            self.required_len, self.capacity
+        )
+    }
+}
+
+impl Error for CapacityError {}
@@ -2,25 +2,101 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[doc(hidden)]
+pub mod __private;
 mod abs_path;
+#[cfg(feature = "alloc")]
 mod abs_path_buf;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary_gen;
+mod case_insensitive;
+#[cfg(feature = "clap")]
+pub mod clap_parsers;
 mod r#const;
+#[cfg(feature = "alloc")]
+mod format_path;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod generate;
+#[cfg(feature = "alloc")]
+mod indexed_abs_path;
+mod inline_str;
 mod node_name;
+#[cfg(feature = "alloc")]
 mod node_name_buf;
+#[cfg(feature = "alloc")]
+mod path_arena;
+#[cfg(feature = "alloc")]
+mod path_codec;
+mod path_list;
+#[cfg(feature = "alloc")]
+mod path_storage;
+mod portability;
+#[cfg(feature = "proptest")]
+pub mod proptest_gen;
+#[cfg(feature = "std")]
+mod resolve_paths;
+#[cfg(feature = "alloc")]
+mod sanitize;
+mod scan;
+#[cfg(feature = "schemars")]
+mod schema;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub mod serde_as;
+mod stack_abs_path_buf;
+mod stack_node_name_buf;
+#[cfg(feature = "unicode-normalization")]
+mod unicode;
 
 #[cfg(feature = "std")]
 pub use abs_path::AbsPathFromPathError;
-pub use abs_path::{
-    AbsPath,
-    AbsPathNotAbsoluteError,
-    Components,
-    NormalizeError,
-};
-pub use abs_path_buf::AbsPathBuf;
+pub use abs_path::{AbsPath, Components, InvalidAbsPathError, NormalizeError};
+#[cfg(feature = "rkyv")]
+pub use abs_path_buf::ArchivedAbsPathBuf;
+#[cfg(feature = "alloc")]
+pub use abs_path_buf::{AbsPathBuf, GenericAbsPathBuf};
+pub use case_insensitive::CaseInsensitive;
+#[cfg(feature = "alloc")]
+pub use format_path::FormatPathError;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub use generate::{Alphabet, GenerateOptions};
+#[cfg(feature = "alloc")]
+pub use indexed_abs_path::{IndexedAbsPath, IndexedAbsPathBuf};
+pub use inline_str::CapacityError;
 pub use node_name::{InvalidNodeNameError, NodeName};
+#[cfg(feature = "rkyv")]
+pub use node_name_buf::ArchivedNodeNameBuf;
+#[cfg(feature = "alloc")]
 pub use node_name_buf::NodeNameBuf;
+#[cfg(feature = "alloc")]
+pub use path_arena::{PathArena, PathId};
+#[cfg(feature = "alloc")]
+pub use path_codec::{PathDecodeError, PathDecoder, PathEncoder};
+#[cfg(feature = "alloc")]
+pub use path_list::RelativePathList;
+pub use path_list::{
+    PathList,
+    PathListError,
+    PathListErrorKind,
+    RecordSeparator,
+};
+#[cfg(feature = "alloc")]
+pub use path_storage::PathStorage;
+pub use portability::{PortabilityError, PortabilityProfile};
+#[cfg(feature = "std")]
+pub use resolve_paths::{
+    ResolvePathError,
+    ResolvePathErrorKind,
+    ResolvePathsError,
+};
+#[cfg(feature = "alloc")]
+pub use sanitize::SanitizeOptions;
+pub use stack_abs_path_buf::StackAbsPathBuf;
+pub use stack_node_name_buf::StackNodeNameBuf;
+#[cfg(feature = "unicode-normalization")]
+pub use unicode::NfcEq;
 
 #[cfg(not(windows))]
 const MAIN_SEPARATOR_CHAR: char = '/';
@@ -1,13 +1,16 @@
+#[cfg(feature = "alloc")]
 use alloc::borrow;
 use core::{error, fmt, ops};
 
-use crate::{NodeNameBuf, r#const};
+#[cfg(feature = "alloc")]
+use crate::NodeNameBuf;
+use crate::{PortabilityError, PortabilityProfile, r#const, scan};
 
 #[cfg(not(target_os = "windows"))]
-const INVALID_CHARACTERS: &[char] = &['/', '\0'];
+pub(crate) const INVALID_CHARACTERS: &[char] = &['/', '\0'];
 
 #[cfg(target_os = "windows")]
-const INVALID_CHARACTERS: &[char] =
+pub(crate) const INVALID_CHARACTERS: &[char] =
     &['<', '>', ':', '"', '/', '\\', '|', '?', '*', '\0', '\r', '\n', '\t'];
 
 /// The borrowed version of [`NodeNameBuf`].
@@ -18,11 +21,24 @@ pub struct NodeName(str);
 impl NodeName {
     /// TODO: docs.
     #[inline]
-    pub fn as_str(&self) -> &str {
+    pub const fn as_str(&self) -> &str {
         &self.0
     }
 
-    /// TODO: docs.
+    /// Returns whether the two names are equal.
+    ///
+    /// This is the same as `self == other`, but it can be called in `const`
+    /// contexts.
+    #[inline]
+    pub const fn eq_const(&self, other: &Self) -> bool {
+        r#const::str_eq(self.as_str(), other.as_str())
+    }
+
+    /// Checks that the given string is a valid file name.
+    ///
+    /// This can be called in `const` contexts, but outside of them
+    /// `<&NodeName>::try_from()` should be preferred, since it's faster on
+    /// long names.
     #[inline]
     pub const fn from_str(str: &str) -> Result<&Self, InvalidNodeNameError> {
         if str.is_empty() {
@@ -31,7 +47,7 @@ impl NodeName {
             Err(InvalidNodeNameError::SingleDot)
         } else if r#const::str_eq(str, "..") {
             Err(InvalidNodeNameError::DoubleDot)
-        } else if let Some(invalid) =
+        } else if let Some((_, invalid)) =
             r#const::str_find_char(str, INVALID_CHARACTERS)
         {
             Err(InvalidNodeNameError::ContainsInvalidCharacter(invalid))
@@ -41,6 +57,20 @@ impl NodeName {
         }
     }
 
+    /// Checks that the file name is also valid under the given
+    /// [`PortabilityProfile`].
+    ///
+    /// This is useful to reject names that are valid on the current platform
+    /// but that would cause problems on others, like `aux.txt` or `foo.` on
+    /// Windows.
+    #[inline]
+    pub fn validate_for(
+        &self,
+        profile: PortabilityProfile,
+    ) -> Result<(), PortabilityError> {
+        profile.validate(self.as_str())
+    }
+
     /// # Safety
     ///
     /// The caller must ensure that the given string is a valid file name.
@@ -78,6 +108,7 @@ impl PartialEq<NodeName> for str {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl borrow::ToOwned for NodeName {
     type Owned = NodeNameBuf;
 
@@ -106,7 +137,7 @@ impl<'a> TryFrom<&'a str> for &'a NodeName {
 
     #[inline]
     fn try_from(str: &'a str) -> Result<Self, Self::Error> {
-        NodeName::from_str(str)
+        scan::node_name_from_str(str)
     }
 }
 
@@ -135,6 +166,13 @@ pub enum InvalidNodeNameError {
 
     /// The file name contains an invalid character.
     ContainsInvalidCharacter(char),
+
+    /// The file name is not valid under a [`PortabilityProfile`].
+    ///
+    /// This is never returned by [`NodeName::from_str`], only by
+    /// conversions from the [`PortabilityError`] returned by
+    /// [`NodeName::validate_for`].
+    NotPortable(PortabilityError),
 }
 
 impl fmt::Display for InvalidNodeNameError {
@@ -147,15 +185,26 @@ impl fmt::Display for InvalidNodeNameError {
             Self::ContainsInvalidCharacter(c) => {
                 write!(f, "file name contains an invalid character: {c:?}")
             },
+            Self::NotPortable(err) => err.fmt(f),
         }
     }
 }
 
 impl error::Error for InvalidNodeNameError {}
 
+impl From<PortabilityError> for InvalidNodeNameError {
+    #[inline]
+    fn from(err: PortabilityError) -> Self {
+        Self::NotPortable(err)
+    }
+}
+
 #[cfg(feature = "serde")]
 mod serde_impls {
-    use serde::de::{Deserialize, Deserializer, Error};
+    use alloc::string::String;
+    use core::fmt;
+
+    use serde::de::{Deserialize, Deserializer, Error, Visitor};
     use serde::ser::{Serialize, Serializer};
 
     use super::NodeName;
@@ -176,9 +225,73 @@ mod serde_impls {
         where
             D: Deserializer<'de>,
         {
-            <&'de str>::deserialize(deserializer)?
-                .try_into()
-                .map_err(D::Error::custom)
+            struct BorrowedVisitor;
+
+            impl<'de> Visitor<'de> for BorrowedVisitor {
+                type Value = &'de NodeName;
+
+                #[inline]
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a borrowed file name")
+                }
+
+                #[inline]
+                fn visit_borrowed_str<E: Error>(
+                    self,
+                    str: &'de str,
+                ) -> Result<Self::Value, E> {
+                    str.try_into().map_err(E::custom)
+                }
+
+                #[inline]
+                fn visit_str<E: Error>(
+                    self,
+                    _: &str,
+                ) -> Result<Self::Value, E> {
+                    Err(E::custom(
+                        "can't borrow the file name from the input, which is \
+                         either escaped or not kept in memory; deserialize \
+                         into a NodeNameBuf instead",
+                    ))
+                }
+
+                #[inline]
+                fn visit_string<E: Error>(
+                    self,
+                    str: String,
+                ) -> Result<Self::Value, E> {
+                    self.visit_str(&str)
+                }
+            }
+
+            deserializer.deserialize_str(BorrowedVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+mod schemars_impls {
+    use alloc::borrow::Cow;
+
+    use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+    use super::NodeName;
+    use crate::schema;
+
+    impl JsonSchema for NodeName {
+        #[inline]
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("NodeName")
+        }
+
+        #[inline]
+        fn schema_id() -> Cow<'static, str> {
+            schema::schema_id("NodeName")
+        }
+
+        #[inline]
+        fn json_schema(_: &mut SchemaGenerator) -> Schema {
+            schema::node_name_schema()
         }
     }
 }
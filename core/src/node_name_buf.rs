@@ -3,7 +3,7 @@ use core::{fmt, ops, str};
 
 use smol_str::SmolStr;
 
-use crate::{InvalidNodeNameError, NodeName};
+use crate::{InvalidNodeNameError, NodeName, SanitizeOptions, sanitize};
 
 /// TODO: docs.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -11,6 +11,17 @@ pub struct NodeNameBuf {
     inner: SmolStr,
 }
 
+/// The archived form of a [`NodeNameBuf`], which derefs to a [`NodeName`]
+/// without having to be deserialized.
+///
+/// Validating it with `bytecheck` checks the same invariants as
+/// [`NodeName::from_str`].
+#[cfg(feature = "rkyv")]
+#[repr(transparent)]
+pub struct ArchivedNodeNameBuf {
+    inner: rkyv::string::ArchivedString,
+}
+
 impl NodeNameBuf {
     /// TODO: docs.
     #[inline]
@@ -18,6 +29,31 @@ impl NodeNameBuf {
         self.inner.as_str()
     }
 
+    /// Creates an owned file name from a `'static` one without allocating.
+    ///
+    /// Short names are stored inline and longer ones keep a reference to the
+    /// static string, so this can be used to initialize `const`s and
+    /// `static`s.
+    #[inline]
+    pub const fn from_static(name: &'static NodeName) -> Self {
+        Self { inner: SmolStr::new_static(name.as_str()) }
+    }
+
+    /// Turns arbitrary text into a valid file name.
+    ///
+    /// Invalid characters are replaced or stripped, names that would be
+    /// empty or refer to `.` or `..` are escaped, and names longer than the
+    /// maximum length are truncated while trying to preserve their extension.
+    /// See [`SanitizeOptions`] for how to customize this.
+    ///
+    /// The returned boolean is `true` if the name differs from the input.
+    #[inline]
+    pub fn sanitize(str: &str, options: &SanitizeOptions) -> (Self, bool) {
+        let sanitized = sanitize::sanitize(str, options);
+        let changed = sanitized != str;
+        (Self::new(sanitized.into()), changed)
+    }
+
     #[inline]
     pub(crate) fn new(inner: SmolStr) -> Self {
         Self { inner }
@@ -101,3 +137,186 @@ mod serde_impls {
         }
     }
 }
+
+#[cfg(feature = "rkyv")]
+mod rkyv_impls {
+    use core::hash::{Hash, Hasher};
+    use core::ops::Deref;
+    use core::{fmt, ptr};
+
+    use rkyv::bytecheck::CheckBytes;
+    use rkyv::rancor::{Fallible, Source};
+    use rkyv::string::{ArchivedString, StringResolver};
+    use rkyv::{
+        Archive,
+        Deserialize,
+        Place,
+        Portable,
+        Serialize,
+        SerializeUnsized,
+    };
+
+    use super::{ArchivedNodeNameBuf, NodeNameBuf};
+    use crate::{NodeName, scan};
+
+    impl Archive for NodeNameBuf {
+        type Archived = ArchivedNodeNameBuf;
+        type Resolver = StringResolver;
+
+        #[inline]
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            // SAFETY: `ArchivedNodeNameBuf` is a transparent wrapper around
+            // an `ArchivedString`.
+            let out = unsafe { out.cast_unchecked::<ArchivedString>() };
+            ArchivedString::resolve_from_str(self.as_str(), resolver, out);
+        }
+    }
+
+    impl<S> Serialize<S> for NodeNameBuf
+    where
+        S: Fallible + ?Sized,
+        S::Error: Source,
+        str: SerializeUnsized<S>,
+    {
+        #[inline]
+        fn serialize(
+            &self,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            ArchivedString::serialize_from_str(self.as_str(), serializer)
+        }
+    }
+
+    impl<D: Fallible + ?Sized> Deserialize<NodeNameBuf, D>
+        for ArchivedNodeNameBuf
+    {
+        #[inline]
+        fn deserialize(&self, _: &mut D) -> Result<NodeNameBuf, D::Error> {
+            Ok(NodeNameBuf::new(self.as_str().into()))
+        }
+    }
+
+    // SAFETY: `ArchivedString` is `Portable`, and this is a transparent
+    // wrapper around it.
+    unsafe impl Portable for ArchivedNodeNameBuf {}
+
+    // SAFETY: the string is checked first, and then validated as a name.
+    unsafe impl<C> CheckBytes<C> for ArchivedNodeNameBuf
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+        ArchivedString: CheckBytes<C>,
+    {
+        #[inline]
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            let inner = unsafe { ptr::addr_of!((*value).inner) };
+            unsafe { ArchivedString::check_bytes(inner, context)? };
+            let str = unsafe { &*inner }.as_str();
+            scan::node_name_from_str(str).map_err(C::Error::new)?;
+            Ok(())
+        }
+    }
+
+    impl Deref for ArchivedNodeNameBuf {
+        type Target = NodeName;
+
+        #[inline]
+        fn deref(&self) -> &Self::Target {
+            // SAFETY: the string was either validated by `check_bytes` or
+            // archived from a valid name.
+            unsafe { NodeName::from_str_unchecked(self.inner.as_str()) }
+        }
+    }
+
+    impl AsRef<NodeName> for ArchivedNodeNameBuf {
+        #[inline]
+        fn as_ref(&self) -> &NodeName {
+            self
+        }
+    }
+
+    impl fmt::Debug for ArchivedNodeNameBuf {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_tuple("ArchivedNodeNameBuf").field(&self.as_str()).finish()
+        }
+    }
+
+    impl fmt::Display for ArchivedNodeNameBuf {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Display::fmt(&**self, f)
+        }
+    }
+
+    impl Hash for ArchivedNodeNameBuf {
+        #[inline]
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.deref().hash(state);
+        }
+    }
+
+    impl PartialEq for ArchivedNodeNameBuf {
+        #[inline]
+        fn eq(&self, other: &Self) -> bool {
+            **self == **other
+        }
+    }
+
+    impl Eq for ArchivedNodeNameBuf {}
+
+    impl PartialEq<NodeName> for ArchivedNodeNameBuf {
+        #[inline]
+        fn eq(&self, other: &NodeName) -> bool {
+            &**self == other
+        }
+    }
+
+    impl PartialEq<NodeNameBuf> for ArchivedNodeNameBuf {
+        #[inline]
+        fn eq(&self, other: &NodeNameBuf) -> bool {
+            **self == **other
+        }
+    }
+
+    impl PartialEq<str> for ArchivedNodeNameBuf {
+        #[inline]
+        fn eq(&self, other: &str) -> bool {
+            self.as_str() == other
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+mod schemars_impls {
+    use alloc::borrow::Cow;
+
+    use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+    use super::NodeNameBuf;
+    use crate::schema;
+
+    impl JsonSchema for NodeNameBuf {
+        #[inline]
+        fn schema_name() -> Cow<'static, str> {
+            Cow::Borrowed("NodeName")
+        }
+
+        #[inline]
+        fn schema_id() -> Cow<'static, str> {
+            schema::schema_id("NodeName")
+        }
+
+        #[inline]
+        fn json_schema(_: &mut SchemaGenerator) -> Schema {
+            schema::node_name_schema()
+        }
+    }
+}
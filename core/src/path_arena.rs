@@ -0,0 +1,321 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Index, Range};
+
+use crate::AbsPath;
+
+/// A collection of [`AbsPath`]s stored in a single contiguous buffer.
+///
+/// Storing many [`AbsPathBuf`](crate::AbsPathBuf)s separately costs one heap
+/// allocation per path that doesn't fit inline. An arena instead appends the
+/// bytes of every path to the same buffer, and identifies each path with a
+/// 4-byte [`PathId`].
+///
+/// Calling [`share_ancestors()`](Self::share_ancestors) after the arena is
+/// filled makes every path that's an ancestor of another one share its
+/// bytes, which for typical file trees removes every directory from the
+/// buffer.
+///
+/// Only ancestors can share bytes, since every path has to be a contiguous
+/// slice of the buffer to be returned as an [`&AbsPath`](AbsPath). Siblings
+/// like `/src/a.rs` and `/src/b.rs` each store their common `/src` prefix.
+/// To store the shared prefixes only once, e.g. to write the paths to disk,
+/// use a [`PathEncoder`](crate::PathEncoder) instead.
+///
+/// # Panics
+///
+/// Methods that add paths panic if the buffer would exceed [`u32::MAX`]
+/// bytes, or if the arena would contain more than [`u32::MAX`] paths.
+#[derive(Clone, Default)]
+pub struct PathArena {
+    buf: String,
+    spans: Vec<Span>,
+
+    /// The offset of the last path written to the buffer, which new paths
+    /// can share bytes with.
+    tail_start: u32,
+}
+
+/// A handle to a path stored in a [`PathArena`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PathId(u32);
+
+#[derive(Copy, Clone)]
+struct Span {
+    start: u32,
+    len: u32,
+}
+
+impl PathArena {
+    /// Returns the number of bytes used by the buffer backing the arena.
+    #[inline]
+    pub fn buffer_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Rebuilds the buffer so that every path that's an ancestor of another
+    /// path in the arena points into the bytes of its descendant, and the
+    /// bytes of the paths removed by [`dedup()`](Self::dedup) are freed.
+    ///
+    /// Paths that aren't ancestors of each other still take up their full
+    /// length, even if they share some leading components.
+    ///
+    /// This doesn't change the [`PathId`]s or the order of the paths.
+    #[inline]
+    pub fn share_ancestors(&mut self) {
+        let mut order = (0..self.spans.len()).collect::<Vec<_>>();
+        order.sort_unstable_by(|&lhs, &rhs| {
+            cmp_components(
+                self.path(self.spans[lhs]),
+                self.path(self.spans[rhs]),
+            )
+        });
+
+        let mut buf = String::new();
+        let mut spans = self.spans.clone();
+        let mut prev: Option<(&AbsPath, Span)> = None;
+        let mut tail_start = 0;
+
+        // Sorting by components puts every path right before its
+        // descendants, so we walk the paths backwards and share the bytes of
+        // the previous path whenever the current one is one of its ancestors.
+        for &idx in order.iter().rev() {
+            let path = self.path(self.spans[idx]);
+            let span = match prev {
+                Some((prev_path, prev_span))
+                    if prev_path.starts_with(path) =>
+                {
+                    Span { start: prev_span.start, len: path.len() as u32 }
+                },
+                _ => {
+                    let span = push_str(&mut buf, path.as_str());
+                    tail_start = span.start;
+                    span
+                },
+            };
+            spans[idx] = span;
+            prev = Some((path, span));
+        }
+
+        buf.shrink_to_fit();
+        self.buf = buf;
+        self.spans = spans;
+        self.tail_start = tail_start;
+    }
+
+    /// Removes consecutive duplicate paths, like [`Vec::dedup`].
+    ///
+    /// Call [`sort()`](Self::sort) first to remove all the duplicates. This
+    /// invalidates the previously returned [`PathId`]s.
+    #[inline]
+    pub fn dedup(&mut self) {
+        let buf = &self.buf;
+        self.spans
+            .dedup_by(|lhs, rhs| span_str(buf, *lhs) == span_str(buf, *rhs));
+    }
+
+    /// Pushes all the paths yielded by the given iterator, returning the
+    /// [`PathId`] of the first one.
+    #[inline]
+    pub fn extend_from_iter<I>(&mut self, paths: I) -> PathId
+    where
+        I: IntoIterator,
+        I::Item: AsRef<AbsPath>,
+    {
+        let first = self.next_id();
+        let paths = paths.into_iter();
+        self.spans.reserve(paths.size_hint().0);
+        for path in paths {
+            self.push(path);
+        }
+        first
+    }
+
+    /// Returns the path with the given ID, or `None` if the ID is out of
+    /// bounds.
+    #[inline]
+    pub fn get(&self, id: PathId) -> Option<&AbsPath> {
+        self.spans.get(id.0 as usize).map(|&span| self.path(span))
+    }
+
+    /// Returns `true` if the arena doesn't contain any paths.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns an iterator over the paths in the arena and their IDs.
+    #[inline]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (PathId, &AbsPath)> {
+        self.spans
+            .iter()
+            .enumerate()
+            .map(|(idx, &span)| (PathId(idx as u32), self.path(span)))
+    }
+
+    /// Returns the number of paths in the arena.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Creates a new, empty arena.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the given path to the arena, returning its ID.
+    ///
+    /// If the path is an ancestor of the last path written to the buffer, or
+    /// if that path is one of its ancestors, their bytes are shared.
+    #[inline]
+    pub fn push<P: AsRef<AbsPath>>(&mut self, path: P) -> PathId {
+        let path = path.as_ref();
+        let id = self.next_id();
+
+        let span = match self.tail() {
+            Some(tail) if tail.starts_with(path) => {
+                Span { start: self.tail_start, len: path.len() as u32 }
+            },
+            Some(tail) if path.starts_with(tail) => {
+                let suffix = &path.as_str()[tail.len()..];
+                push_str(&mut self.buf, suffix);
+                Span { start: self.tail_start, len: path.len() as u32 }
+            },
+            _ => {
+                let span = push_str(&mut self.buf, path.as_str());
+                self.tail_start = span.start;
+                span
+            },
+        };
+
+        self.spans.push(span);
+        id
+    }
+
+    /// Sorts the paths by comparing their components, which puts every path
+    /// right before its descendants.
+    ///
+    /// This invalidates the previously returned [`PathId`]s.
+    #[inline]
+    pub fn sort(&mut self) {
+        let buf = &self.buf;
+        self.spans.sort_unstable_by(|&lhs, &rhs| {
+            cmp_components(span_path(buf, lhs), span_path(buf, rhs))
+        });
+    }
+
+    /// Creates a new, empty arena with room for at least `paths` paths
+    /// totaling `bytes` bytes.
+    #[inline]
+    pub fn with_capacity(paths: usize, bytes: usize) -> Self {
+        Self {
+            buf: String::with_capacity(bytes),
+            spans: Vec::with_capacity(paths),
+            tail_start: 0,
+        }
+    }
+
+    #[inline]
+    fn next_id(&self) -> PathId {
+        PathId(
+            u32::try_from(self.spans.len())
+                .expect("arena contains too many paths"),
+        )
+    }
+
+    #[inline]
+    fn path(&self, span: Span) -> &AbsPath {
+        span_path(&self.buf, span)
+    }
+
+    #[inline]
+    fn tail(&self) -> Option<&AbsPath> {
+        (!self.buf.is_empty()).then(|| {
+            // SAFETY: the tail always starts at the beginning of a path and
+            // ends at the end of one.
+            unsafe {
+                AbsPath::from_str_unchecked(
+                    &self.buf[self.tail_start as usize..],
+                )
+            }
+        })
+    }
+}
+
+impl PathId {
+    /// Returns the index of the path in its arena.
+    #[inline]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[inline]
+fn cmp_components(lhs: &AbsPath, rhs: &AbsPath) -> Ordering {
+    lhs.components()
+        .map(|name| name.as_str())
+        .cmp(rhs.components().map(|name| name.as_str()))
+}
+
+#[inline]
+fn push_str(buf: &mut String, str: &str) -> Span {
+    let start = buf.len();
+    let end = start + str.len();
+    assert!(end <= u32::MAX as usize, "arena buffer exceeds u32::MAX bytes");
+    buf.push_str(str);
+    Span { start: start as u32, len: str.len() as u32 }
+}
+
+#[inline]
+fn span_str(buf: &str, span: Span) -> &str {
+    &buf[Range::from(span)]
+}
+
+#[inline]
+fn span_path(buf: &str, span: Span) -> &AbsPath {
+    // SAFETY: every span covers a valid absolute path.
+    unsafe { AbsPath::from_str_unchecked(span_str(buf, span)) }
+}
+
+impl From<Span> for Range<usize> {
+    #[inline]
+    fn from(span: Span) -> Self {
+        span.start as usize..(span.start + span.len) as usize
+    }
+}
+
+impl Index<PathId> for PathArena {
+    type Output = AbsPath;
+
+    #[inline]
+    fn index(&self, id: PathId) -> &Self::Output {
+        self.get(id).expect("path ID out of bounds")
+    }
+}
+
+impl<P: AsRef<AbsPath>> Extend<P> for PathArena {
+    #[inline]
+    fn extend<I: IntoIterator<Item = P>>(&mut self, paths: I) {
+        self.extend_from_iter(paths);
+    }
+}
+
+impl<P: AsRef<AbsPath>> FromIterator<P> for PathArena {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = P>>(paths: I) -> Self {
+        let mut arena = Self::new();
+        arena.extend_from_iter(paths);
+        arena
+    }
+}
+
+impl fmt::Debug for PathArena {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter().map(|(_, path)| path)).finish()
+    }
+}
@@ -0,0 +1,276 @@
+use alloc::vec::Vec;
+use core::error::Error;
+use core::{fmt, str};
+
+use crate::{AbsPath, AbsPathBuf, InvalidNodeNameError, NodeName, scan};
+
+/// The bytes every encoded path sequence starts with.
+const MAGIC: [u8; 4] = *b"apth";
+
+/// Encodes a sequence of [`AbsPath`]s into a compact binary format.
+///
+/// Every path is stored as the number of leading components it shares with
+/// the previous path, followed by its remaining components. Sorting the
+/// paths component-wise first (e.g. with
+/// [`PathArena::sort()`](crate::PathArena::sort)) maximizes the number of
+/// shared components, but any order can be encoded.
+///
+/// The output starts with a header containing the format's
+/// [`VERSION`](Self::VERSION), and can be read back with a [`PathDecoder`].
+#[derive(Clone, Debug)]
+pub struct PathEncoder {
+    buf: Vec<u8>,
+    prev: AbsPathBuf,
+    prev_depth: usize,
+}
+
+/// Decodes a sequence of paths encoded by a [`PathEncoder`].
+///
+/// The components are borrowed from the input, so they can be read without
+/// allocating via [`next_components`](Self::next_components). This also
+/// implements [`Iterator`] for when owned paths are needed.
+#[derive(Clone, Debug)]
+pub struct PathDecoder<'a> {
+    bytes: &'a [u8],
+    components: Vec<&'a NodeName>,
+    offset: usize,
+}
+
+/// The error type returned when decoding an invalid path sequence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PathDecodeError {
+    /// The input doesn't start with the expected header.
+    InvalidHeader,
+
+    /// The input was encoded with an unsupported version of the format.
+    UnsupportedVersion(u8),
+
+    /// The input ends in the middle of a record.
+    UnexpectedEof,
+
+    /// The variable-length integer at the given byte offset doesn't fit in
+    /// a `usize`.
+    InvalidVarint { byte_offset: usize },
+
+    /// The record at the given byte offset shares more components with the
+    /// previous path than it has.
+    InvalidSharedDepth { byte_offset: usize },
+
+    /// The component at the given byte offset is not valid UTF-8.
+    NotUtf8 { byte_offset: usize },
+
+    /// The component at the given byte offset is not a valid node name.
+    InvalidComponent { byte_offset: usize, err: InvalidNodeNameError },
+}
+
+impl PathEncoder {
+    /// The version of the format written by the encoder.
+    pub const VERSION: u8 = 1;
+
+    /// Returns the encoded bytes.
+    #[inline]
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    /// Creates a new encoder, writing the header.
+    #[inline]
+    pub fn new() -> Self {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC);
+        buf.push(Self::VERSION);
+        Self { buf, prev: AbsPathBuf::root(), prev_depth: 0 }
+    }
+
+    /// Appends the given path to the sequence.
+    #[inline]
+    pub fn push<P: AsRef<AbsPath>>(&mut self, path: P) -> &mut Self {
+        let path = path.as_ref();
+
+        let shared_depth = self
+            .prev
+            .components()
+            .zip(path.components())
+            .take_while(|(prev, next)| prev == next)
+            .count();
+
+        for _ in shared_depth..self.prev_depth {
+            self.prev.pop();
+        }
+
+        let num_new = path.components().skip(shared_depth).count();
+
+        write_varint(&mut self.buf, shared_depth);
+        write_varint(&mut self.buf, num_new);
+
+        for component in path.components().skip(shared_depth) {
+            write_varint(&mut self.buf, component.len());
+            self.buf.extend_from_slice(component.as_str().as_bytes());
+            self.prev.push(component);
+        }
+
+        self.prev_depth = shared_depth + num_new;
+        self
+    }
+}
+
+impl<'a> PathDecoder<'a> {
+    /// Creates a new decoder over the given bytes, checking the header.
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> Result<Self, PathDecodeError> {
+        let Some(rest) = bytes.strip_prefix(&MAGIC) else {
+            return Err(PathDecodeError::InvalidHeader);
+        };
+
+        match rest.first() {
+            Some(&PathEncoder::VERSION) => {},
+            Some(&version) => {
+                return Err(PathDecodeError::UnsupportedVersion(version));
+            },
+            None => return Err(PathDecodeError::InvalidHeader),
+        }
+
+        Ok(Self { bytes, components: Vec::new(), offset: MAGIC.len() + 1 })
+    }
+
+    /// Returns the components of the next path, or `None` if there are no
+    /// more paths.
+    ///
+    /// The root is returned as an empty slice. After an error is returned,
+    /// the decoder always returns `None`.
+    #[inline]
+    pub fn next_components(
+        &mut self,
+    ) -> Option<Result<&[&'a NodeName], PathDecodeError>> {
+        if self.offset == self.bytes.len() {
+            return None;
+        }
+        match self.decode_record() {
+            Ok(()) => Some(Ok(&self.components)),
+            Err(err) => {
+                self.offset = self.bytes.len();
+                Some(Err(err))
+            },
+        }
+    }
+
+    #[inline]
+    fn decode_record(&mut self) -> Result<(), PathDecodeError> {
+        let record_offset = self.offset;
+        let shared_depth = self.read_varint()?;
+        let num_new = self.read_varint()?;
+
+        if shared_depth > self.components.len() {
+            return Err(PathDecodeError::InvalidSharedDepth {
+                byte_offset: record_offset,
+            });
+        }
+
+        self.components.truncate(shared_depth);
+
+        for _ in 0..num_new {
+            let len = self.read_varint()?;
+            let byte_offset = self.offset;
+            let bytes = self
+                .bytes
+                .get(byte_offset..byte_offset.saturating_add(len))
+                .ok_or(PathDecodeError::UnexpectedEof)?;
+            let str = str::from_utf8(bytes)
+                .map_err(|_| PathDecodeError::NotUtf8 { byte_offset })?;
+            let component = scan::node_name_from_str(str).map_err(|err| {
+                PathDecodeError::InvalidComponent { byte_offset, err }
+            })?;
+            self.components.push(component);
+            self.offset += len;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn read_varint(&mut self) -> Result<usize, PathDecodeError> {
+        let byte_offset = self.offset;
+        let mut value = 0usize;
+        let mut shift = 0;
+
+        loop {
+            let &byte = self
+                .bytes
+                .get(self.offset)
+                .ok_or(PathDecodeError::UnexpectedEof)?;
+            self.offset += 1;
+
+            let bits = (byte & 0x7F) as usize;
+            if shift >= usize::BITS || (bits << shift) >> shift != bits {
+                return Err(PathDecodeError::InvalidVarint { byte_offset });
+            }
+            value |= bits << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+}
+
+/// Writes the given value as an unsigned LEB128 integer.
+#[inline]
+fn write_varint(buf: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+impl Default for PathEncoder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for PathDecoder<'_> {
+    type Item = Result<AbsPathBuf, PathDecodeError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_components().map(|res| {
+            res.map(|components| components.iter().copied().collect())
+        })
+    }
+}
+
+impl fmt::Display for PathDecodeError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidHeader => f.write_str("invalid header"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {version}")
+            },
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            Self::InvalidVarint { byte_offset } => {
+                write!(f, "integer at byte {byte_offset} overflows a usize")
+            },
+            Self::InvalidSharedDepth { byte_offset } => write!(
+                f,
+                "record at byte {byte_offset} shares more components than \
+                 the previous path has"
+            ),
+            Self::NotUtf8 { byte_offset } => {
+                write!(f, "component at byte {byte_offset} is not valid UTF-8")
+            },
+            Self::InvalidComponent { byte_offset, err } => {
+                write!(f, "invalid component at byte {byte_offset}: {err}")
+            },
+        }
+    }
+}
+
+impl Error for PathDecodeError {}
@@ -0,0 +1,331 @@
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::error::Error;
+use core::{fmt, str};
+
+#[cfg(feature = "alloc")]
+use crate::MAIN_SEPARATOR_STR;
+use crate::{AbsPath, InvalidAbsPathError, NormalizeError, scan};
+
+/// The byte separating the records of a path list.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RecordSeparator {
+    /// Records are separated by `\n`, like in the default output of
+    /// `git ls-files` or `find`.
+    ///
+    /// A `\r` right before the separator is stripped, so lists with CRLF line
+    /// endings are also accepted.
+    Newline,
+
+    /// Records are separated by `\0`, like in the output of
+    /// `git ls-files -z` or `find -print0`.
+    Nul,
+}
+
+/// An iterator over the absolute paths in a buffer containing a list of
+/// paths separated by a [`RecordSeparator`].
+///
+/// Every path borrows from the buffer, so no allocation is made. Empty
+/// records, like the one after a trailing separator, are skipped, but they
+/// still count towards the record numbers reported in errors.
+///
+/// The records must be valid paths as accepted by [`AbsPath::from_str`], so
+/// `.` and `..` components or repeated separators are errors. To normalize
+/// them instead, use [`relative_to`](Self::relative_to) with
+/// [`AbsPath::root()`] as the base, which normalizes absolute records.
+#[derive(Clone, Debug)]
+pub struct PathList<'buf> {
+    records: Records<'buf>,
+}
+
+/// Like [`PathList`], but for lists of relative paths which are joined to a
+/// base path, like the output of `git ls-files`.
+///
+/// The paths are built in a buffer that's reused across records, so this
+/// can't implement [`Iterator`]. Use [`next_path`](Self::next_path) instead.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct RelativePathList<'buf> {
+    allow_escape: bool,
+    base_len: usize,
+    buf: String,
+    records: Records<'buf>,
+}
+
+/// The error type returned when a record of a path list is not a valid path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PathListError {
+    byte_offset: usize,
+    kind: PathListErrorKind,
+    record: usize,
+}
+
+/// The reason why a record of a path list was rejected.
+///
+/// The byte offsets in the wrapped errors are relative to the start of the
+/// record.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathListErrorKind {
+    /// The record of a [`PathList`] is not a valid absolute path.
+    Invalid(InvalidAbsPathError),
+
+    /// The record of a [`RelativePathList`] is relative, but its `..`
+    /// components navigate outside of the base path.
+    EscapesBase,
+
+    /// The record of a [`RelativePathList`] couldn't be normalized.
+    Normalize(NormalizeError),
+
+    /// The record is not valid UTF-8.
+    NotUtf8,
+}
+
+#[derive(Clone, Debug)]
+struct Records<'buf> {
+    buf: &'buf [u8],
+    offset: usize,
+    record: usize,
+    separator: u8,
+}
+
+struct Record<'buf> {
+    byte_offset: usize,
+    record: usize,
+    str: &'buf str,
+}
+
+impl<'buf> PathList<'buf> {
+    /// Creates a new list over the given buffer.
+    #[inline]
+    pub fn new(buf: &'buf [u8], separator: RecordSeparator) -> Self {
+        Self { records: Records::new(buf, separator) }
+    }
+
+    /// Turns this into a list of relative paths joined to the given base.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn relative_to(self, base: &AbsPath) -> RelativePathList<'buf> {
+        RelativePathList {
+            allow_escape: false,
+            base_len: base.len(),
+            buf: base.as_str().into(),
+            records: self.records,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'buf> RelativePathList<'buf> {
+    /// Sets whether relative records can navigate outside of the base path
+    /// with `..` components.
+    ///
+    /// This is `false` by default, in which case such records are reported
+    /// as [`PathListErrorKind::EscapesBase`] errors.
+    #[inline]
+    pub fn allow_escape(mut self, allow: bool) -> Self {
+        self.allow_escape = allow;
+        self
+    }
+
+    /// Returns the next path, or `None` if there are no more records.
+    ///
+    /// Records are normalized after being joined to the base, so they can
+    /// contain `.` and `..` components, as long as the result is still inside
+    /// the base unless [`allow_escape`](Self::allow_escape) is set. Records
+    /// that are already absolute are only normalized.
+    ///
+    /// The returned path only borrows from the internal buffer unless the
+    /// normalization had to allocate.
+    #[inline]
+    pub fn next_path(
+        &mut self,
+    ) -> Option<Result<Cow<'_, AbsPath>, PathListError>> {
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let is_relative = !record.str.starts_with(MAIN_SEPARATOR_STR);
+
+        let to_normalize = if !is_relative {
+            record.str
+        } else {
+            self.buf.truncate(self.base_len);
+            self.buf.push_str(MAIN_SEPARATOR_STR);
+            self.buf.push_str(record.str);
+            &self.buf
+        };
+
+        // The record is always a suffix of the normalized string.
+        let prefix_len = to_normalize.len() - record.str.len();
+
+        let path = match AbsPath::normalize(to_normalize) {
+            Ok(path) => path,
+            Err(err) => {
+                let err = match err {
+                    NormalizeError::InvalidCharacter { byte_offset, ch } => {
+                        NormalizeError::InvalidCharacter {
+                            byte_offset: byte_offset - prefix_len,
+                            ch,
+                        }
+                    },
+                    other => other,
+                };
+                return Some(Err(
+                    record.error(PathListErrorKind::Normalize(err))
+                ));
+            },
+        };
+
+        let base = &self.buf[..self.base_len];
+
+        if is_relative && !self.allow_escape && !is_inside(&path, base) {
+            return Some(Err(record.error(PathListErrorKind::EscapesBase)));
+        }
+
+        Some(Ok(path))
+    }
+}
+
+/// Returns whether the path is equal to or a descendant of the base.
+#[cfg(feature = "alloc")]
+#[inline]
+fn is_inside(path: &AbsPath, base: &str) -> bool {
+    match path.as_str().strip_prefix(base) {
+        Some(rest) => {
+            rest.is_empty()
+                || base == MAIN_SEPARATOR_STR
+                || rest.starts_with(MAIN_SEPARATOR_STR)
+        },
+        None => false,
+    }
+}
+
+impl PathListError {
+    /// Returns the offset of the start of the record in the buffer.
+    #[inline]
+    pub fn byte_offset(&self) -> usize {
+        self.byte_offset
+    }
+
+    /// Returns the reason why the record was rejected.
+    #[inline]
+    pub fn kind(&self) -> PathListErrorKind {
+        self.kind
+    }
+
+    /// Returns the one-based number of the record, i.e. the line number for
+    /// [`RecordSeparator::Newline`].
+    #[inline]
+    pub fn record(&self) -> usize {
+        self.record
+    }
+}
+
+impl<'buf> Records<'buf> {
+    #[inline]
+    fn new(buf: &'buf [u8], separator: RecordSeparator) -> Self {
+        let separator = match separator {
+            RecordSeparator::Newline => b'\n',
+            RecordSeparator::Nul => b'\0',
+        };
+        Self { buf, offset: 0, record: 0, separator }
+    }
+}
+
+impl Record<'_> {
+    #[inline]
+    fn error(&self, kind: PathListErrorKind) -> PathListError {
+        PathListError {
+            byte_offset: self.byte_offset,
+            kind,
+            record: self.record,
+        }
+    }
+}
+
+impl<'buf> Iterator for Records<'buf> {
+    type Item = Result<Record<'buf>, PathListError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.offset >= self.buf.len() {
+                return None;
+            }
+
+            let rest = &self.buf[self.offset..];
+            let len =
+                memchr::memchr(self.separator, rest).unwrap_or(rest.len());
+            let byte_offset = self.offset;
+            self.offset += len + 1;
+            self.record += 1;
+
+            let mut bytes = &rest[..len];
+
+            if self.separator == b'\n' {
+                bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+            }
+
+            if bytes.is_empty() {
+                continue;
+            }
+
+            let record = Record { byte_offset, record: self.record, str: "" };
+
+            return Some(match str::from_utf8(bytes) {
+                Ok(str) => Ok(Record { str, ..record }),
+                Err(_) => Err(record.error(PathListErrorKind::NotUtf8)),
+            });
+        }
+    }
+}
+
+impl<'buf> Iterator for PathList<'buf> {
+    type Item = Result<&'buf AbsPath, PathListError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.records.next()?.and_then(|record| {
+            scan::abs_path_from_str(record.str)
+                .map_err(|err| record.error(PathListErrorKind::Invalid(err)))
+        }))
+    }
+}
+
+impl fmt::Display for PathListError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid path in record {}: {}", self.record, self.kind)
+    }
+}
+
+impl Error for PathListError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            PathListErrorKind::Invalid(err) => Some(err),
+            PathListErrorKind::Normalize(err) => Some(err),
+            PathListErrorKind::EscapesBase | PathListErrorKind::NotUtf8 => {
+                None
+            },
+        }
+    }
+}
+
+impl fmt::Display for PathListErrorKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(err) => err.fmt(f),
+            Self::EscapesBase => {
+                f.write_str("relative path navigates outside of the base path")
+            },
+            Self::Normalize(err) => err.fmt(f),
+            Self::NotUtf8 => f.write_str("record is not valid UTF-8"),
+        }
+    }
+}
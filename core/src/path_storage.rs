@@ -0,0 +1,158 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+
+use compact_str::CompactString;
+
+/// The string type backing a [`GenericAbsPathBuf`](crate::GenericAbsPathBuf).
+///
+/// This lets callers pick the representation that best fits how the paths
+/// are used, e.g. [`String`] for cheap conversions to
+/// [`PathBuf`](std::path::PathBuf)s, or [`Arc<str>`] for cheap clones. The
+/// default is [`CompactString`], which stores short paths inline.
+///
+/// Immutable strings like [`Arc<str>`] are supported by reallocating on every
+/// mutation.
+///
+/// # Safety
+///
+/// [`as_str()`](Self::as_str) must always return the string the storage was
+/// created with, as modified by the subsequent calls to
+/// [`push_str()`](Self::push_str) and [`truncate()`](Self::truncate). The
+/// path types rely on this to uphold their invariants without re-validating
+/// the string on every access.
+pub unsafe trait PathStorage: Sized {
+    /// Returns the stored string.
+    fn as_str(&self) -> &str;
+
+    /// Creates a new storage containing the given string.
+    fn from_str(str: &str) -> Self;
+
+    /// Appends the given string to the end of the stored one.
+    fn push_str(&mut self, str: &str);
+
+    /// Shortens the stored string to the given length in bytes, which is
+    /// guaranteed to be on a char boundary.
+    fn truncate(&mut self, new_len: usize);
+
+    /// Creates a new storage from an owned string.
+    ///
+    /// The default implementation calls [`from_str()`](Self::from_str), but
+    /// types that can reuse the string's allocation should override it.
+    #[inline]
+    fn from_string(string: String) -> Self {
+        Self::from_str(&string)
+    }
+
+    /// Converts the storage into an owned string.
+    ///
+    /// The default implementation copies [`as_str()`](Self::as_str), but
+    /// types that can give up their allocation should override it.
+    #[inline]
+    fn into_string(self) -> String {
+        self.as_str().into()
+    }
+}
+
+unsafe impl PathStorage for CompactString {
+    #[inline]
+    fn as_str(&self) -> &str {
+        self
+    }
+
+    #[inline]
+    fn from_str(str: &str) -> Self {
+        Self::new(str)
+    }
+
+    #[inline]
+    fn push_str(&mut self, str: &str) {
+        self.push_str(str);
+    }
+
+    #[inline]
+    fn truncate(&mut self, new_len: usize) {
+        self.truncate(new_len);
+    }
+
+    #[inline]
+    fn from_string(string: String) -> Self {
+        string.into()
+    }
+
+    #[inline]
+    fn into_string(self) -> String {
+        self.into()
+    }
+}
+
+unsafe impl PathStorage for String {
+    #[inline]
+    fn as_str(&self) -> &str {
+        self
+    }
+
+    #[inline]
+    fn from_str(str: &str) -> Self {
+        str.into()
+    }
+
+    #[inline]
+    fn push_str(&mut self, str: &str) {
+        self.push_str(str);
+    }
+
+    #[inline]
+    fn truncate(&mut self, new_len: usize) {
+        self.truncate(new_len);
+    }
+
+    #[inline]
+    fn from_string(string: String) -> Self {
+        string
+    }
+
+    #[inline]
+    fn into_string(self) -> String {
+        self
+    }
+}
+
+macro_rules! impl_path_storage_for_immutable {
+    ($ty:ty) => {
+        unsafe impl PathStorage for $ty {
+            #[inline]
+            fn as_str(&self) -> &str {
+                self
+            }
+
+            #[inline]
+            fn from_str(str: &str) -> Self {
+                str.into()
+            }
+
+            #[inline]
+            fn push_str(&mut self, str: &str) {
+                let mut string = String::with_capacity(self.len() + str.len());
+                string.push_str(self);
+                string.push_str(str);
+                *self = string.into();
+            }
+
+            #[inline]
+            fn truncate(&mut self, new_len: usize) {
+                *self = self[..new_len].into();
+            }
+
+            #[inline]
+            fn from_string(string: String) -> Self {
+                string.into()
+            }
+        }
+    };
+}
+
+impl_path_storage_for_immutable!(Box<str>);
+impl_path_storage_for_immutable!(Rc<str>);
+impl_path_storage_for_immutable!(Arc<str>);
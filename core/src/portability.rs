@@ -0,0 +1,249 @@
+use core::{error, fmt};
+
+/// Characters that can't appear in file names on Windows, in addition to the
+/// ASCII control characters.
+const WINDOWS_INVALID_CHARACTERS: &[char] =
+    &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Characters that can't appear in file names on macOS.
+///
+/// The colon is technically allowed by APFS, but it's shown as a slash by the
+/// Finder and rejected by most Carbon-era APIs.
+const MACOS_INVALID_CHARACTERS: &[char] = &['/', ':'];
+
+/// Characters that can't appear in file names on POSIX systems.
+const POSIX_INVALID_CHARACTERS: &[char] = &['/'];
+
+/// File names that refer to devices on Windows, regardless of their case and
+/// of any extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &["CON", "PRN", "AUX", "NUL"];
+
+/// Prefixes that refer to numbered devices on Windows when followed by a
+/// digit from 1 to 9 (including the superscript digits `¹`, `²` and `³`).
+const WINDOWS_RESERVED_NUMBERED_PREFIXES: &[&str] = &["COM", "LPT"];
+
+/// The maximum length of a file name in bytes on most POSIX file systems.
+const MAX_LEN_BYTES: usize = 255;
+
+/// The maximum length of a file name in UTF-16 code units on Windows.
+const MAX_LEN_UTF16: usize = 255;
+
+/// A set of platform-specific rules that a [`NodeName`](crate::NodeName) can
+/// be validated against via
+/// [`NodeName::validate_for`](crate::NodeName::validate_for).
+///
+/// The checks done by [`NodeName::from_str`](crate::NodeName::from_str) only
+/// depend on the platform the crate is compiled for, so a name that's valid
+/// on Linux can still be impossible to check out on Windows or macOS. These
+/// profiles let callers opt into stricter rules.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PortabilityProfile {
+    /// Names must be at most 255 bytes long and can't contain `/`.
+    Posix,
+
+    /// Names must be at most 255 UTF-16 code units long, can't contain
+    /// control characters or any of `<>:"/\|?*`, can't end with a dot or a
+    /// space, and can't be a reserved device name like `CON` or `aux.txt`.
+    Windows,
+
+    /// Names must be at most 255 bytes long and can't contain `/` or `:`.
+    MacOS,
+
+    /// The union of all the other profiles, i.e. names that are valid on
+    /// every supported platform.
+    Portable,
+}
+
+/// The error type returned when a [`NodeName`](crate::NodeName) is not valid
+/// under a [`PortabilityProfile`].
+///
+/// This is separate from [`InvalidNodeNameError`] because a `NodeName` has
+/// already passed the checks behind that error, so callers of
+/// [`validate_for`](crate::NodeName::validate_for) only have to handle the
+/// profile-specific failures. It converts into
+/// [`InvalidNodeNameError::NotPortable`] for code that parses and validates
+/// names with a single error type.
+///
+/// [`InvalidNodeNameError`]: crate::InvalidNodeNameError
+/// [`InvalidNodeNameError::NotPortable`]: crate::InvalidNodeNameError::NotPortable
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PortabilityError {
+    /// The file name contains a character that's not allowed by the profile.
+    ContainsInvalidCharacter(char),
+
+    /// The file name contains an ASCII control character, which is not
+    /// allowed by the [`Windows`](PortabilityProfile::Windows) profile.
+    ContainsControlCharacter(char),
+
+    /// The file name ends with a dot, which is silently stripped on Windows.
+    TrailingDot,
+
+    /// The file name ends with a space, which is silently stripped on
+    /// Windows.
+    TrailingSpace,
+
+    /// The file name refers to a reserved device on Windows, like `CON` or
+    /// `aux.txt`.
+    ReservedName,
+
+    /// The file name is longer than the maximum length allowed by the
+    /// profile.
+    ///
+    /// The unit of both lengths depends on the profile: UTF-16 code units
+    /// for [`Windows`](PortabilityProfile::Windows), bytes for the others.
+    TooLong { len: usize, max_len: usize },
+}
+
+impl PortabilityProfile {
+    /// Returns an error if the given character can't appear in a file name
+    /// under this profile.
+    #[inline]
+    pub(crate) fn check_char(self, ch: char) -> Result<(), PortabilityError> {
+        let (invalid_chars, reject_control) = match self {
+            Self::Posix => (POSIX_INVALID_CHARACTERS, false),
+            Self::MacOS => (MACOS_INVALID_CHARACTERS, false),
+            // The Windows characters are a superset of the others.
+            Self::Windows | Self::Portable => {
+                (WINDOWS_INVALID_CHARACTERS, true)
+            },
+        };
+        if reject_control && ch.is_ascii_control() {
+            Err(PortabilityError::ContainsControlCharacter(ch))
+        } else if invalid_chars.contains(&ch) {
+            Err(PortabilityError::ContainsInvalidCharacter(ch))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns whether this profile follows the Windows rules about trailing
+    /// dots and spaces and reserved device names.
+    #[inline]
+    pub(crate) fn has_windows_rules(self) -> bool {
+        matches!(self, Self::Windows | Self::Portable)
+    }
+
+    /// Returns whether the given name is a reserved device name under this
+    /// profile.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn is_reserved_name(self, name: &str) -> bool {
+        self.has_windows_rules() && is_windows_reserved_name(name)
+    }
+
+    /// Returns the maximum length of a file name in bytes that's guaranteed
+    /// to be accepted by this profile.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn max_len_bytes(self) -> usize {
+        match self {
+            Self::Posix | Self::MacOS | Self::Portable => MAX_LEN_BYTES,
+            // Every UTF-16 code unit takes at least one byte in UTF-8.
+            Self::Windows => MAX_LEN_UTF16,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn validate(self, name: &str) -> Result<(), PortabilityError> {
+        name.chars().try_for_each(|ch| self.check_char(ch))?;
+
+        if self.has_windows_rules() {
+            validate_windows(name)?;
+        }
+
+        match self {
+            Self::Posix | Self::MacOS | Self::Portable => {
+                validate_len(name.len(), MAX_LEN_BYTES)
+            },
+            Self::Windows => {
+                validate_len(name.encode_utf16().count(), MAX_LEN_UTF16)
+            },
+        }
+    }
+}
+
+#[inline]
+fn validate_windows(name: &str) -> Result<(), PortabilityError> {
+    if name.ends_with('.') {
+        return Err(PortabilityError::TrailingDot);
+    }
+
+    if name.ends_with(' ') {
+        return Err(PortabilityError::TrailingSpace);
+    }
+
+    if is_windows_reserved_name(name) {
+        return Err(PortabilityError::ReservedName);
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn validate_len(len: usize, max_len: usize) -> Result<(), PortabilityError> {
+    if len > max_len {
+        Err(PortabilityError::TooLong { len, max_len })
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns whether the given name refers to a device on Windows.
+///
+/// The check is done on the part of the name before the first dot, with any
+/// trailing spaces removed, because Windows ignores both the extension and
+/// the spaces when resolving device names.
+#[inline]
+fn is_windows_reserved_name(name: &str) -> bool {
+    let stem = match name.split_once('.') {
+        Some((stem, _)) => stem,
+        None => name,
+    };
+
+    let stem = stem.trim_end_matches(' ');
+
+    if WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+    {
+        return true;
+    }
+
+    WINDOWS_RESERVED_NUMBERED_PREFIXES.iter().any(|prefix| {
+        let Some(head) = stem.get(..prefix.len()) else { return false };
+        if !head.eq_ignore_ascii_case(prefix) {
+            return false;
+        }
+        let mut rest = stem[prefix.len()..].chars();
+        matches!(
+            (rest.next(), rest.next()),
+            (Some('1'..='9' | '¹' | '²' | '³'), None)
+        )
+    })
+}
+
+impl fmt::Display for PortabilityError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ContainsInvalidCharacter(c) => {
+                write!(f, "file name contains an invalid character: {c:?}")
+            },
+            Self::ContainsControlCharacter(c) => {
+                write!(f, "file name contains a control character: {c:?}")
+            },
+            Self::TrailingDot => write!(f, "file name ends with a dot"),
+            Self::TrailingSpace => write!(f, "file name ends with a space"),
+            Self::ReservedName => {
+                write!(f, "file name is a reserved device name")
+            },
+            Self::TooLong { len, max_len } => write!(
+                f,
+                "file name is too long: its length is {len}, but the maximum \
+                 is {max_len}"
+            ),
+        }
+    }
+}
+
+impl error::Error for PortabilityError {}
@@ -0,0 +1,95 @@
+//! Strategies for the [`proptest`] crate, for property-testing code against
+//! the real invariants of the path types.
+//!
+//! Paths are generated from a list of components, so they shrink by first
+//! removing components and then by shortening the remaining ones.
+
+use alloc::string::String;
+
+use ::proptest::arbitrary::Arbitrary;
+use ::proptest::collection::vec;
+use ::proptest::prelude::{BoxedStrategy, Just, Strategy, any, prop_oneof};
+use ::proptest::sample::select;
+
+use crate::generate::{self, GenerateOptions};
+use crate::{GenericAbsPathBuf, MAIN_SEPARATOR_STR, NodeNameBuf, PathStorage};
+
+/// Returns a strategy generating valid node names.
+#[inline]
+pub fn node_name_buf(
+    options: GenerateOptions,
+) -> impl Strategy<Value = NodeNameBuf> + Clone {
+    let ch = match options.get_alphabet().chars() {
+        Some(chars) => select(chars).boxed(),
+        None => any::<char>()
+            .prop_map(|ch| if generate::is_valid_char(ch) { ch } else { '_' })
+            .boxed(),
+    };
+
+    vec(ch, 1..=options.get_max_name_len()).prop_map(|chars| {
+        let mut name = chars.into_iter().collect::<String>();
+        generate::escape_dots(&mut name);
+        NodeNameBuf::new(name.into())
+    })
+}
+
+/// Returns a strategy generating valid absolute paths.
+#[inline]
+pub fn abs_path_buf<S: PathStorage>(
+    options: GenerateOptions,
+) -> impl Strategy<Value = GenericAbsPathBuf<S>> + Clone {
+    vec(node_name_buf(options), 0..=options.get_max_depth()).prop_map(
+        |names| {
+            names.iter().map(|name| &**name).collect::<GenericAbsPathBuf<S>>()
+        },
+    )
+}
+
+/// Returns a strategy generating strings that start with a separator, but
+/// whose components can also be empty, `.` or `..`, like the inputs of
+/// [`AbsPath::normalize`](crate::AbsPath::normalize).
+#[inline]
+pub fn almost_valid_path(
+    options: GenerateOptions,
+) -> impl Strategy<Value = String> + Clone {
+    let component = prop_oneof![
+        3 => node_name_buf(options).prop_map(|name| String::from(name.as_str())),
+        1 => Just(String::from(".")),
+        1 => Just(String::from("..")),
+        1 => Just(String::new()),
+    ];
+
+    (vec(component, 0..=options.get_max_depth()), any::<bool>()).prop_map(
+        |(components, trailing_separator)| {
+            let mut path = String::new();
+            for component in &components {
+                path.push_str(MAIN_SEPARATOR_STR);
+                path.push_str(component);
+            }
+            if path.is_empty() || trailing_separator {
+                path.push_str(MAIN_SEPARATOR_STR);
+            }
+            path
+        },
+    )
+}
+
+impl Arbitrary for NodeNameBuf {
+    type Parameters = GenerateOptions;
+    type Strategy = BoxedStrategy<Self>;
+
+    #[inline]
+    fn arbitrary_with(options: Self::Parameters) -> Self::Strategy {
+        node_name_buf(options).boxed()
+    }
+}
+
+impl<S: PathStorage + 'static> Arbitrary for GenericAbsPathBuf<S> {
+    type Parameters = GenerateOptions;
+    type Strategy = BoxedStrategy<Self>;
+
+    #[inline]
+    fn arbitrary_with(options: Self::Parameters) -> Self::Strategy {
+        abs_path_buf(options).boxed()
+    }
+}
@@ -0,0 +1,231 @@
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use crate::{AbsPath, AbsPathBuf, MAIN_SEPARATOR_STR, NormalizeError};
+
+/// The error type returned by the `resolve_paths()` method generated by
+/// `#[derive(ResolvePaths)]`, containing every field that couldn't be
+/// resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvePathsError {
+    errors: Vec<ResolvePathError>,
+}
+
+/// The error for a single field that couldn't be resolved.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ResolvePathError {
+    field: &'static str,
+    kind: ResolvePathErrorKind,
+}
+
+/// The reason why a field couldn't be resolved.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResolvePathErrorKind {
+    /// The field's value is a relative path whose `..` components navigate
+    /// above the base path, and the field is not marked `allow_escape`.
+    EscapesBase,
+
+    /// The field is marked `must_exist`, but nothing exists at the resolved
+    /// path.
+    DoesNotExist,
+
+    /// The field's value starts with a `~`, but the home directory couldn't
+    /// be determined or is not a valid absolute path.
+    HomeDirUnavailable,
+
+    /// The field's value couldn't be normalized. Byte offsets are relative
+    /// to the field's value.
+    Invalid(NormalizeError),
+}
+
+/// The per-field options set by the `#[resolve(..)]` attribute.
+#[doc(hidden)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ResolveOptions {
+    pub allow_escape: bool,
+    pub expand_tilde: bool,
+    pub must_exist: bool,
+}
+
+/// Resolves the fields of a struct deriving `ResolvePaths` one at a time,
+/// collecting the errors.
+#[doc(hidden)]
+pub struct PathResolver<'base> {
+    base: &'base AbsPath,
+    errors: Vec<ResolvePathError>,
+}
+
+impl ResolvePathsError {
+    /// Returns the errors, in the order in which the fields are declared.
+    #[inline]
+    pub fn errors(&self) -> &[ResolvePathError] {
+        &self.errors
+    }
+}
+
+impl ResolvePathError {
+    /// Returns the name of the field that couldn't be resolved.
+    #[inline]
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    /// Returns the reason why the field couldn't be resolved.
+    #[inline]
+    pub fn kind(&self) -> ResolvePathErrorKind {
+        self.kind
+    }
+}
+
+impl<'base> PathResolver<'base> {
+    /// Returns the error containing every field that failed to resolve.
+    #[inline]
+    pub fn into_error(self) -> ResolvePathsError {
+        debug_assert!(!self.errors.is_empty());
+        ResolvePathsError { errors: self.errors }
+    }
+
+    /// Creates a resolver for relative paths based at the given path.
+    #[inline]
+    pub fn new(base: &'base AbsPath) -> Self {
+        Self { base, errors: Vec::new() }
+    }
+
+    /// Resolves the value of the given field, returning `None` and recording
+    /// the error if it fails.
+    #[inline]
+    pub fn resolve(
+        &mut self,
+        field: &'static str,
+        value: &str,
+        options: ResolveOptions,
+    ) -> Option<AbsPathBuf> {
+        match resolve(self.base, value, options) {
+            Ok(path) => Some(path),
+            Err(kind) => {
+                self.errors.push(ResolvePathError { field, kind });
+                None
+            },
+        }
+    }
+}
+
+/// Resolves `value` against `base`.
+///
+/// Absolute paths and paths starting with `~` are only normalized, so they
+/// never count as escaping the base.
+fn resolve(
+    base: &AbsPath,
+    value: &str,
+    options: ResolveOptions,
+) -> Result<AbsPathBuf, ResolvePathErrorKind> {
+    let home;
+
+    let (anchor, relative, is_relative) = if value
+        .starts_with(MAIN_SEPARATOR_STR)
+    {
+        (AbsPath::root(), value, false)
+    } else if options.expand_tilde
+        && let Some(rest) = strip_tilde(value)
+    {
+        home = home_dir().ok_or(ResolvePathErrorKind::HomeDirUnavailable)?;
+        (&*home, rest, false)
+    } else {
+        (base, value, true)
+    };
+
+    let joined = alloc::format!("{anchor}{MAIN_SEPARATOR_STR}{relative}");
+
+    let path = AbsPath::normalize(&joined)
+        .map_err(|err| {
+            // Make the offsets relative to the original value, which is a
+            // suffix of the joined string except for the leading `~`.
+            let prefix_len = joined.len() - value.len();
+            ResolvePathErrorKind::Invalid(match err {
+                NormalizeError::InvalidCharacter { byte_offset, ch } => {
+                    NormalizeError::InvalidCharacter {
+                        byte_offset: byte_offset.saturating_sub(prefix_len),
+                        ch,
+                    }
+                },
+                other => other,
+            })
+        })?
+        .into_owned();
+
+    if is_relative && !options.allow_escape && !path.starts_with(base) {
+        return Err(ResolvePathErrorKind::EscapesBase);
+    }
+
+    if options.must_exist && !std::fs::exists(&path).unwrap_or(false) {
+        return Err(ResolvePathErrorKind::DoesNotExist);
+    }
+
+    Ok(path)
+}
+
+/// Returns the part of the value after the `~` if it's either `~` or starts
+/// with `~` followed by a separator.
+#[inline]
+fn strip_tilde(value: &str) -> Option<&str> {
+    let rest = value.strip_prefix('~')?;
+    (rest.is_empty() || rest.starts_with(MAIN_SEPARATOR_STR)).then_some(rest)
+}
+
+#[inline]
+fn home_dir() -> Option<AbsPathBuf> {
+    let home = std::env::home_dir()?;
+    <&AbsPath>::try_from(&*home).ok().map(ToOwned::to_owned)
+}
+
+impl fmt::Display for ResolvePathsError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &*self.errors {
+            [err] => err.fmt(f),
+            errors => {
+                write!(f, "couldn't resolve {} paths:", errors.len())?;
+                for err in errors {
+                    write!(f, "\n  - {err}")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl Error for ResolvePathsError {}
+
+impl fmt::Display for ResolvePathError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "couldn't resolve field `{}`: {}", self.field, self.kind)
+    }
+}
+
+impl Error for ResolvePathError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ResolvePathErrorKind::Invalid(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ResolvePathErrorKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EscapesBase => {
+                f.write_str("path escapes the base directory via `..`")
+            },
+            Self::DoesNotExist => f.write_str("path does not exist"),
+            Self::HomeDirUnavailable => {
+                f.write_str("couldn't determine the home directory")
+            },
+            Self::Invalid(err) => err.fmt(f),
+        }
+    }
+}
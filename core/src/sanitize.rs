@@ -0,0 +1,236 @@
+use alloc::string::String;
+
+use crate::node_name::INVALID_CHARACTERS;
+use crate::{NodeName, PortabilityProfile};
+
+/// The character used by [`SanitizeOptions::default()`] to replace invalid
+/// characters and to escape names that can't be used as-is.
+const DEFAULT_REPLACEMENT: char = '_';
+
+/// Options controlling how [`NodeNameBuf::sanitize`] turns arbitrary text
+/// into a valid file name.
+///
+/// [`NodeNameBuf::sanitize`]: crate::NodeNameBuf::sanitize
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SanitizeOptions {
+    max_len: Option<usize>,
+    profile: Option<PortabilityProfile>,
+    replacement: Option<char>,
+}
+
+impl SanitizeOptions {
+    /// Sets the maximum length of the sanitized name in bytes.
+    ///
+    /// Longer names are truncated on a character boundary, preserving the
+    /// extension if there's room for it. When a
+    /// [`profile`](Self::profile) is set, its own limit is used if it's
+    /// smaller.
+    ///
+    /// A file name can't be empty, so limits shorter than the character
+    /// used for escaping (1 byte for the default `_`) are raised to its
+    /// length.
+    #[inline]
+    pub const fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Sanitizes the name so that it also passes
+    /// [`NodeName::validate_for`] with the given profile.
+    #[inline]
+    pub const fn profile(mut self, profile: PortabilityProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Sets the character invalid characters are replaced with, or `None` to
+    /// strip them instead.
+    ///
+    /// The replacement (or `_` if there's none) is also used to escape names
+    /// that can't be used as-is, like `..` or reserved device names. If it's
+    /// itself invalid, invalid characters are stripped and `_` is used for
+    /// escaping. The same happens for escaping if the replacement is a dot or
+    /// a space.
+    #[inline]
+    pub const fn replacement(mut self, replacement: Option<char>) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    #[inline]
+    fn escape_char(&self) -> char {
+        match self.replacement {
+            Some(ch) if ch != '.' && ch != ' ' && self.is_valid_char(ch) => ch,
+            _ => DEFAULT_REPLACEMENT,
+        }
+    }
+
+    #[inline]
+    fn is_valid_char(&self, ch: char) -> bool {
+        !INVALID_CHARACTERS.contains(&ch)
+            && self
+                .profile
+                .is_none_or(|profile| profile.check_char(ch).is_ok())
+    }
+
+    #[inline]
+    fn max_len_bytes(&self) -> Option<usize> {
+        let max_len =
+            match (self.max_len, self.profile.map(|p| p.max_len_bytes())) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        max_len.map(|max_len| max_len.max(self.escape_char().len_utf8()))
+    }
+}
+
+impl Default for SanitizeOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_len: None,
+            profile: None,
+            replacement: Some(DEFAULT_REPLACEMENT),
+        }
+    }
+}
+
+/// Returns the sanitized version of `str`, which is guaranteed to be a valid
+/// [`NodeName`].
+pub(crate) fn sanitize(str: &str, options: &SanitizeOptions) -> String {
+    let escape_char = options.escape_char();
+
+    let replacement =
+        options.replacement.filter(|&ch| options.is_valid_char(ch));
+
+    let mut name = String::with_capacity(str.len());
+
+    for ch in str.chars() {
+        if options.is_valid_char(ch) {
+            name.push(ch);
+        } else if let Some(replacement) = replacement {
+            name.push(replacement);
+        }
+    }
+
+    let has_windows_rules =
+        options.profile.is_some_and(PortabilityProfile::has_windows_rules);
+
+    // Truncating first makes sure none of the fixes below are undone, e.g.
+    // by cutting an escaped `auxiliary` back to `aux`.
+    let max_len = options.max_len_bytes();
+
+    if let Some(max_len) = max_len {
+        truncate(&mut name, max_len);
+    }
+
+    if has_windows_rules {
+        trim_trailing_dots_and_spaces(&mut name);
+    }
+
+    if let Some(profile) = options.profile
+        && profile.is_reserved_name(&name)
+    {
+        escape_reserved_name(&mut name, profile, escape_char, max_len);
+        trim_trailing_dots_and_spaces(&mut name);
+    }
+
+    if matches!(&*name, "" | "." | "..") {
+        // Replace every dot so that the result is never a relative
+        // component, as long as the escape characters fit.
+        let max_count = max_len
+            .map_or(usize::MAX, |max_len| max_len / escape_char.len_utf8());
+        let count = name.len().clamp(1, max_count);
+        name.clear();
+        name.extend(core::iter::repeat_n(escape_char, count));
+    }
+
+    debug_assert!(
+        NodeName::from_str(&name).is_ok()
+            && options
+                .profile
+                .is_none_or(|profile| profile.validate(&name).is_ok()),
+        "sanitized name {name:?} is not valid"
+    );
+
+    name
+}
+
+/// Escapes a reserved device name like `aux.txt` by inserting the escape
+/// character right after its stem, e.g. `aux_.txt`, dropping the end of the
+/// stem if needed to stay within `max_len` bytes.
+///
+/// `max_len` must be at least the length of the escape character.
+#[inline]
+fn escape_reserved_name(
+    name: &mut String,
+    profile: PortabilityProfile,
+    escape_char: char,
+    max_len: Option<usize>,
+) {
+    let mut stem_len = name.find('.').unwrap_or(name.len());
+
+    // Make room for the escape character by dropping the end of the stem,
+    // which also keeps the name from being reserved. Once the stem is gone
+    // the end of the extension goes, which can leave a trailing dot.
+    while max_len
+        .is_some_and(|max_len| name.len() + escape_char.len_utf8() > max_len)
+    {
+        if stem_len > 0 {
+            stem_len = floor_char_boundary(name, stem_len - 1);
+            name.remove(stem_len);
+        } else if name.pop().is_none() {
+            break;
+        }
+    }
+
+    name.insert(stem_len, escape_char);
+
+    // An escape character like `1` can turn a shortened stem back into a
+    // reserved name, e.g. `COM5` into `COM1`, but no reserved name starts
+    // with a character followed by a complete reserved name or a prefix of
+    // one, so moving it to the front always works.
+    if stem_len > 0 && profile.is_reserved_name(name) {
+        name.remove(stem_len);
+        name.insert(0, escape_char);
+    }
+}
+
+/// Truncates the name to at most `max_len` bytes on a character boundary,
+/// keeping its extension if it fits.
+#[inline]
+fn truncate(name: &mut String, max_len: usize) {
+    if name.len() <= max_len {
+        return;
+    }
+
+    let extension_start = match name.rfind('.') {
+        Some(0) | None => name.len(),
+        Some(offset) => offset,
+    };
+
+    let extension_len = name.len() - extension_start;
+
+    if extension_len < max_len {
+        let stem_len = floor_char_boundary(name, max_len - extension_len);
+        name.replace_range(stem_len..extension_start, "");
+    } else {
+        name.truncate(floor_char_boundary(name, max_len));
+    }
+}
+
+#[inline]
+fn trim_trailing_dots_and_spaces(name: &mut String) {
+    let trimmed_len = name.trim_end_matches(['.', ' ']).len();
+    name.truncate(trimmed_len);
+}
+
+/// Returns the largest character boundary in `str` that's at most `idx`.
+#[inline]
+fn floor_char_boundary(str: &str, idx: usize) -> usize {
+    let mut idx = idx.min(str.len());
+    while !str.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
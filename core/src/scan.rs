@@ -0,0 +1,167 @@
+//! Non-`const` versions of the validation routines, which are used at
+//! runtime.
+//!
+//! The `const fn`s in the `const` module have to decode the input
+//! one character at a time. Since every invalid character and the path
+//! separator are ASCII, we can instead scan the raw bytes, which lets
+//! `memchr` use SIMD on the common platforms.
+
+use crate::node_name::INVALID_CHARACTERS;
+use crate::{
+    AbsPath,
+    InvalidAbsPathError,
+    InvalidNodeNameError,
+    MAIN_SEPARATOR_CHAR,
+    NodeName,
+};
+
+const SEPARATOR: u8 = MAIN_SEPARATOR_CHAR as u8;
+
+/// Whether each byte is an invalid character inside a node name, i.e. one of
+/// [`INVALID_CHARACTERS`].
+#[cfg(target_os = "windows")]
+const INVALID_BYTES: [bool; 256] = {
+    let mut table = [false; 256];
+    let mut idx = 0;
+    while idx < INVALID_CHARACTERS.len() {
+        table[INVALID_CHARACTERS[idx] as usize] = true;
+        idx += 1;
+    }
+    table
+};
+
+const _: () = {
+    let mut idx = 0;
+    while idx < INVALID_CHARACTERS.len() {
+        assert!(INVALID_CHARACTERS[idx].is_ascii());
+        idx += 1;
+    }
+};
+
+/// Same as [`AbsPath::from_str`].
+#[inline]
+pub(crate) fn abs_path_from_str(
+    str: &str,
+) -> Result<&AbsPath, InvalidAbsPathError> {
+    let bytes = str.as_bytes();
+
+    if bytes.first() != Some(&SEPARATOR) {
+        return Err(InvalidAbsPathError::NotAbsolute);
+    }
+
+    if bytes.len() == 1 {
+        return Ok(AbsPath::root());
+    }
+
+    // Scan the whole path for invalid bytes at once. Components before the
+    // first invalid byte can then only fail because they're empty or dots.
+    let first_invalid = find_invalid_in_path(bytes);
+
+    let mut component_start = 1;
+    let mut component_idx = 0;
+
+    loop {
+        let component_end = match find_separator(&bytes[component_start..]) {
+            Some(len) => component_start + len,
+            None => bytes.len(),
+        };
+
+        let byte_offset = component_start;
+
+        match &bytes[component_start..component_end] {
+            [] => {
+                return Err(InvalidAbsPathError::EmptyComponent {
+                    byte_offset,
+                    component_idx,
+                });
+            },
+            [b'.'] => {
+                return Err(InvalidAbsPathError::SingleDotComponent {
+                    byte_offset,
+                    component_idx,
+                });
+            },
+            [b'.', b'.'] => {
+                return Err(InvalidAbsPathError::DoubleDotComponent {
+                    byte_offset,
+                    component_idx,
+                });
+            },
+            _ => {},
+        }
+
+        if let Some(invalid_offset) = first_invalid
+            && invalid_offset < component_end
+        {
+            return Err(InvalidAbsPathError::InvalidCharacter {
+                byte_offset: invalid_offset,
+                component_idx,
+                ch: bytes[invalid_offset] as char,
+            });
+        }
+
+        if component_end == bytes.len() {
+            break;
+        }
+
+        component_start = component_end + 1;
+        component_idx += 1;
+    }
+
+    // SAFETY: just checked that the string is a valid absolute path.
+    Ok(unsafe { AbsPath::from_str_unchecked(str) })
+}
+
+/// Returns the offset of the first byte of the path that's not allowed in a
+/// node name, ignoring the path separators.
+#[inline]
+fn find_invalid_in_path(bytes: &[u8]) -> Option<usize> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        debug_assert!(INVALID_CHARACTERS == ['/', '\0']);
+        memchr::memchr(b'\0', bytes)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        bytes.iter().position(|&byte| {
+            byte != SEPARATOR && INVALID_BYTES[byte as usize]
+        })
+    }
+}
+
+/// Returns the offset of the first path separator in the given bytes.
+#[inline]
+pub(crate) fn find_separator(bytes: &[u8]) -> Option<usize> {
+    memchr::memchr(SEPARATOR, bytes)
+}
+
+/// Same as [`NodeName::from_str`].
+#[inline]
+pub(crate) fn node_name_from_str(
+    str: &str,
+) -> Result<&NodeName, InvalidNodeNameError> {
+    let bytes = str.as_bytes();
+
+    match bytes {
+        [] => return Err(InvalidNodeNameError::Empty),
+        [b'.'] => return Err(InvalidNodeNameError::SingleDot),
+        [b'.', b'.'] => return Err(InvalidNodeNameError::DoubleDot),
+        _ => {},
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    let first_invalid = memchr::memchr2(b'/', b'\0', bytes);
+
+    #[cfg(target_os = "windows")]
+    let first_invalid =
+        bytes.iter().position(|&byte| INVALID_BYTES[byte as usize]);
+
+    match first_invalid {
+        Some(offset) => Err(InvalidNodeNameError::ContainsInvalidCharacter(
+            bytes[offset] as char,
+        )),
+        // SAFETY: checked above.
+        None => Ok(unsafe { NodeName::from_str_unchecked(str) }),
+    }
+}
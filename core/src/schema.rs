@@ -0,0 +1,83 @@
+//! The JSON Schemas shared by the [`schemars`] impls of the path types.
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+
+use schemars::{Schema, json_schema};
+
+use crate::MAIN_SEPARATOR_CHAR;
+use crate::node_name::INVALID_CHARACTERS;
+
+/// The `format` of the schema for [`AbsPath`](crate::AbsPath) and
+/// [`AbsPathBuf`](crate::AbsPathBuf).
+const ABS_PATH_FORMAT: &str = "abs-path";
+
+/// The `format` of the schema for [`NodeName`](crate::NodeName) and
+/// [`NodeNameBuf`](crate::NodeNameBuf).
+const NODE_NAME_FORMAT: &str = "node-name";
+
+/// Returns the ID shared by the owned and borrowed versions of a type, so
+/// that they're only defined once in a generated schema.
+#[inline]
+pub(crate) fn schema_id(name: &'static str) -> Cow<'static, str> {
+    Cow::Owned(format!("abs_path::{name}"))
+}
+
+/// Returns the schema for absolute paths, i.e. a separator followed by any
+/// number of separator-delimited node names.
+#[inline]
+pub(crate) fn abs_path_schema() -> Schema {
+    let mut separator = String::new();
+    push_escaped(&mut separator, MAIN_SEPARATOR_CHAR);
+    let component = component_pattern();
+
+    json_schema!({
+        "type": "string",
+        "format": ABS_PATH_FORMAT,
+        "pattern": format!("^(?:{separator}|(?:{separator}{component})+)$"),
+    })
+}
+
+/// Returns the schema for node names.
+#[inline]
+pub(crate) fn node_name_schema() -> Schema {
+    json_schema!({
+        "type": "string",
+        "format": NODE_NAME_FORMAT,
+        "pattern": format!("^{}$", component_pattern()),
+    })
+}
+
+/// Returns a pattern matching a single node name: a non-empty string
+/// without any [`INVALID_CHARACTERS`] that's neither `.` nor `..`.
+///
+/// Lookaheads would be shorter, but not every validator supports them.
+#[inline]
+fn component_pattern() -> String {
+    let mut valid = String::from("[^");
+    for &ch in INVALID_CHARACTERS {
+        push_escaped(&mut valid, ch);
+    }
+    let non_dot = format!("{valid}.]");
+    valid.push(']');
+
+    format!(r"(?:{non_dot}{valid}*|\.{non_dot}{valid}*|\.\.{valid}+)")
+}
+
+/// Pushes the character so that it's matched literally both inside and
+/// outside of a character class.
+///
+/// Only ECMA-262 syntax characters are escaped with a backslash, since
+/// escaping anything else is an error in Unicode-aware regexes.
+#[inline]
+fn push_escaped(pattern: &mut String, ch: char) {
+    if ch.is_ascii_control() {
+        pattern.push_str(&format!(r"\u{:04X}", ch as u32));
+    } else {
+        if "^$\\.*+?()[]{}|".contains(ch) {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+}
@@ -0,0 +1,684 @@
+//! Adapters that choose how a path field is represented on the wire.
+//!
+//! By default, paths are (de)serialized as plain strings. Each module in
+//! here provides a `serialize` and a `deserialize` function, so it can be
+//! used with serde's `with` attribute:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Entry {
+//!     #[serde(with = "abs_path::serde_as::as_components")]
+//!     path: AbsPathBuf,
+//! }
+//! ```
+//!
+//! Unlike the default representation, the formats used by these adapters
+//! always separate components with `/`, so they're portable across
+//! platforms.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+use ::serde::ser::{SerializeSeq, Serializer};
+use compact_str::CompactString;
+
+use crate::{
+    AbsPath,
+    AbsPathBuf,
+    GenericAbsPathBuf,
+    MAIN_SEPARATOR_STR,
+    NodeName,
+    NormalizeError,
+    PathStorage,
+};
+
+/// (De)serializes a path as an array of its components, with the root being
+/// an empty array.
+pub mod as_components {
+    use super::*;
+
+    /// Serializes the path as an array of strings.
+    #[inline]
+    pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: AsRef<AbsPath> + ?Sized,
+        S: Serializer,
+    {
+        let path = path.as_ref();
+        let mut seq = serializer.serialize_seq(None)?;
+        for component in path.components() {
+            seq.serialize_element(component.as_str())?;
+        }
+        seq.end()
+    }
+
+    /// Deserializes a path from an array of strings, each of which must be
+    /// a valid [`NodeName`].
+    #[inline]
+    pub fn deserialize<'de, S, D>(
+        deserializer: D,
+    ) -> Result<GenericAbsPathBuf<S>, D::Error>
+    where
+        S: PathStorage,
+        D: Deserializer<'de>,
+    {
+        struct ComponentsVisitor<S>(PhantomData<S>);
+
+        impl<'de, S: PathStorage> Visitor<'de> for ComponentsVisitor<S> {
+            type Value = GenericAbsPathBuf<S>;
+
+            #[inline]
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an array of path components")
+            }
+
+            #[inline]
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut path = GenericAbsPathBuf::root_in();
+                while seq
+                    .next_element_seed(PushComponent(&mut path))?
+                    .is_some()
+                {}
+                Ok(path)
+            }
+        }
+
+        deserializer.deserialize_seq(ComponentsVisitor(PhantomData))
+    }
+}
+
+/// (De)serializes a path relative to a fixed [`Base`](relative_to_base::Base)
+/// directory, e.g. `src/lib.rs` instead of `/home/user/project/src/lib.rs`.
+///
+/// Since serde's `with` attribute can't take generic parameters, the
+/// functions are associated to [`RelativeTo`](relative_to_base::RelativeTo):
+///
+/// ```ignore
+/// struct Project;
+///
+/// impl abs_path::serde_as::relative_to_base::Base for Project {
+///     const PATH: &'static AbsPath = path!("/home/user/project");
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Entry {
+///     #[serde(with = "abs_path::serde_as::relative_to_base::RelativeTo::<Project>")]
+///     path: AbsPathBuf,
+/// }
+/// ```
+pub mod relative_to_base {
+    use super::*;
+
+    /// The directory paths are made relative to.
+    pub trait Base {
+        /// The path of the directory.
+        const PATH: &'static AbsPath;
+    }
+
+    /// A [`Base`] for the root, which (de)serializes paths without their
+    /// leading separator.
+    pub struct Root;
+
+    /// Holds the `serialize` and `deserialize` functions for the base `B`.
+    pub struct RelativeTo<B> {
+        base: PhantomData<B>,
+    }
+
+    impl Base for Root {
+        const PATH: &'static AbsPath = AbsPath::root();
+    }
+
+    impl<B: Base> RelativeTo<B> {
+        /// Serializes the path as a string relative to the base, which is
+        /// empty if the path is the base itself.
+        ///
+        /// Fails if the path is not inside the base.
+        #[inline]
+        pub fn serialize<P, S>(
+            path: &P,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            P: AsRef<AbsPath> + ?Sized,
+            S: Serializer,
+        {
+            let path = path.as_ref();
+            match path.strip_prefix(B::PATH) {
+                Some(suffix) => {
+                    serializer.collect_str(&JoinComponents(suffix))
+                },
+                None => Err(::serde::ser::Error::custom(format_args!(
+                    "path {path} is not inside {}",
+                    B::PATH
+                ))),
+            }
+        }
+
+        /// Deserializes a path from a string relative to the base.
+        #[inline]
+        pub fn deserialize<'de, S, D>(
+            deserializer: D,
+        ) -> Result<GenericAbsPathBuf<S>, D::Error>
+        where
+            S: PathStorage,
+            D: Deserializer<'de>,
+        {
+            struct RelativeVisitor<S, B>(PhantomData<(S, B)>);
+
+            impl<S: PathStorage, B: Base> Visitor<'_> for RelativeVisitor<S, B> {
+                type Value = GenericAbsPathBuf<S>;
+
+                #[inline]
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a relative path")
+                }
+
+                #[inline]
+                fn visit_str<E: de::Error>(
+                    self,
+                    str: &str,
+                ) -> Result<Self::Value, E> {
+                    let mut path = GenericAbsPathBuf::from(B::PATH);
+                    if !str.is_empty() {
+                        push_components(&mut path, str)?;
+                    }
+                    Ok(path)
+                }
+            }
+
+            deserializer.deserialize_str(RelativeVisitor::<S, B>(PhantomData))
+        }
+    }
+}
+
+/// (De)serializes a path as a `file://` URI, percent-encoding every byte
+/// that's not an unreserved URI character.
+pub mod as_file_uri {
+    use super::*;
+
+    /// Serializes the path as a `file:///` URI.
+    #[inline]
+    pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: AsRef<AbsPath> + ?Sized,
+        S: Serializer,
+    {
+        serializer.collect_str(&FileUri(path.as_ref()))
+    }
+
+    /// Deserializes a path from a `file://` URI whose host is either empty
+    /// or `localhost`.
+    ///
+    /// A single trailing `/` is allowed, as it's commonly used for
+    /// directories.
+    #[inline]
+    pub fn deserialize<'de, S, D>(
+        deserializer: D,
+    ) -> Result<GenericAbsPathBuf<S>, D::Error>
+    where
+        S: PathStorage,
+        D: Deserializer<'de>,
+    {
+        struct UriVisitor<S>(PhantomData<S>);
+
+        impl<S: PathStorage> Visitor<'_> for UriVisitor<S> {
+            type Value = GenericAbsPathBuf<S>;
+
+            #[inline]
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a file:// URI")
+            }
+
+            #[inline]
+            fn visit_str<E: de::Error>(
+                self,
+                uri: &str,
+            ) -> Result<Self::Value, E> {
+                let rest = uri.strip_prefix("file://").ok_or_else(|| {
+                    E::custom("URI doesn't start with file://")
+                })?;
+
+                let path = rest.strip_prefix("localhost").unwrap_or(rest);
+
+                let Some(path) = path.strip_prefix('/') else {
+                    return Err(E::custom("URI has a non-local host"));
+                };
+
+                let path = path.strip_suffix('/').unwrap_or(path);
+
+                let mut ret = GenericAbsPathBuf::root_in();
+                if !path.is_empty() {
+                    // Split before decoding, so that an encoded `/` is part
+                    // of a segment and gets rejected as an invalid character.
+                    for segment in path.split('/') {
+                        push_component(&mut ret, &percent_decode(segment)?)?;
+                    }
+                }
+                Ok(ret)
+            }
+        }
+
+        deserializer.deserialize_str(UriVisitor(PhantomData))
+    }
+
+    #[inline]
+    fn percent_decode<E: de::Error>(str: &str) -> Result<String, E> {
+        let mut bytes = Vec::with_capacity(str.len());
+        let mut iter = str.bytes();
+
+        while let Some(byte) = iter.next() {
+            if byte != b'%' {
+                bytes.push(byte);
+                continue;
+            }
+            let hi = iter.next().and_then(hex_value);
+            let lo = iter.next().and_then(hex_value);
+            match (hi, lo) {
+                (Some(hi), Some(lo)) => bytes.push((hi << 4) | lo),
+                _ => return Err(E::custom("invalid percent-encoding in URI")),
+            }
+        }
+
+        String::from_utf8(bytes)
+            .map_err(|_| E::custom("URI is not valid UTF-8 once decoded"))
+    }
+
+    #[inline]
+    fn hex_value(byte: u8) -> Option<u8> {
+        (byte as char).to_digit(16).map(|digit| digit as u8)
+    }
+
+    struct FileUri<'a>(&'a AbsPath);
+
+    impl fmt::Display for FileUri<'_> {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("file://")?;
+            if self.0.is_root() {
+                return f.write_str("/");
+            }
+            for component in self.0.components() {
+                f.write_str("/")?;
+                for &byte in component.as_str().as_bytes() {
+                    if byte.is_ascii_alphanumeric()
+                        || matches!(byte, b'-' | b'.' | b'_' | b'~')
+                    {
+                        write!(f, "{}", byte as char)?;
+                    } else {
+                        write!(f, "%{byte:02X}")?;
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// (De)serializes a path that comes from the OS and may not be valid UTF-8.
+///
+/// Paths are serialized as strings, but they can be deserialized from
+/// strings, byte strings, or arrays of bytes, with the invalid UTF-8
+/// sequences replaced by `U+FFFD`.
+pub mod lossy_os {
+    use super::*;
+
+    /// Serializes the path as a string.
+    #[inline]
+    pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: AsRef<AbsPath> + ?Sized,
+        S: Serializer,
+    {
+        serializer.serialize_str(path.as_ref().as_str())
+    }
+
+    /// Deserializes a path from a string or from bytes, decoding the latter
+    /// lossily.
+    #[inline]
+    pub fn deserialize<'de, S, D>(
+        deserializer: D,
+    ) -> Result<GenericAbsPathBuf<S>, D::Error>
+    where
+        S: PathStorage,
+        D: Deserializer<'de>,
+    {
+        struct LossyVisitor<S>(PhantomData<S>);
+
+        impl<'de, S: PathStorage> Visitor<'de> for LossyVisitor<S> {
+            type Value = GenericAbsPathBuf<S>;
+
+            #[inline]
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a path as a string or as bytes")
+            }
+
+            #[inline]
+            fn visit_str<E: de::Error>(
+                self,
+                str: &str,
+            ) -> Result<Self::Value, E> {
+                GenericAbsPathBuf::try_from(str).map_err(E::custom)
+            }
+
+            #[inline]
+            fn visit_string<E: de::Error>(
+                self,
+                string: String,
+            ) -> Result<Self::Value, E> {
+                GenericAbsPathBuf::try_from(string).map_err(E::custom)
+            }
+
+            #[inline]
+            fn visit_bytes<E: de::Error>(
+                self,
+                bytes: &[u8],
+            ) -> Result<Self::Value, E> {
+                match String::from_utf8_lossy(bytes) {
+                    Cow::Borrowed(str) => self.visit_str(str),
+                    Cow::Owned(string) => self.visit_string(string),
+                }
+            }
+
+            #[inline]
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut bytes =
+                    Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                self.visit_bytes(&bytes)
+            }
+        }
+
+        deserializer.deserialize_byte_buf(LossyVisitor(PhantomData))
+    }
+}
+
+/// Deserializes a `Cow<'de, AbsPath>` that borrows from the input whenever
+/// the format allows it.
+///
+/// serde's own `Deserialize` impl for `Cow` always deserializes the owned
+/// variant, so this has to be opted into with
+/// `#[serde(borrow, with = "abs_path::serde_as::cow")]`.
+pub mod cow {
+    use super::*;
+
+    /// Serializes the path as a string.
+    #[inline]
+    pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: AsRef<AbsPath> + ?Sized,
+        S: Serializer,
+    {
+        serializer.serialize_str(path.as_ref().as_str())
+    }
+
+    /// Deserializes a path from a string, borrowing it if possible.
+    #[inline]
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<Cow<'de, AbsPath>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CowVisitor;
+
+        impl<'de> Visitor<'de> for CowVisitor {
+            type Value = Cow<'de, AbsPath>;
+
+            #[inline]
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an absolute path")
+            }
+
+            #[inline]
+            fn visit_borrowed_str<E: de::Error>(
+                self,
+                str: &'de str,
+            ) -> Result<Self::Value, E> {
+                <&AbsPath>::try_from(str).map(Cow::Borrowed).map_err(E::custom)
+            }
+
+            #[inline]
+            fn visit_str<E: de::Error>(
+                self,
+                str: &str,
+            ) -> Result<Self::Value, E> {
+                AbsPathBuf::try_from(str).map(Cow::Owned).map_err(E::custom)
+            }
+
+            #[inline]
+            fn visit_string<E: de::Error>(
+                self,
+                string: String,
+            ) -> Result<Self::Value, E> {
+                AbsPathBuf::try_from(string).map(Cow::Owned).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(CowVisitor)
+    }
+}
+
+/// Deserializes a path by [normalizing](AbsPath::normalize) it instead of
+/// rejecting it, so that e.g. `/srv//data/./cache/` becomes
+/// `/srv/data/cache`.
+///
+/// Relative paths are still rejected. Use a [`NormalizeSeed`] to resolve
+/// them against a base.
+pub mod normalize {
+    use super::*;
+
+    /// Serializes the path as a string.
+    #[inline]
+    pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: AsRef<AbsPath> + ?Sized,
+        S: Serializer,
+    {
+        serializer.serialize_str(path.as_ref().as_str())
+    }
+
+    /// Deserializes a path from a string, normalizing it.
+    #[inline]
+    pub fn deserialize<'de, S, D>(
+        deserializer: D,
+    ) -> Result<GenericAbsPathBuf<S>, D::Error>
+    where
+        S: PathStorage,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(NormalizeVisitor::<S> {
+            base: None,
+            storage: PhantomData,
+        })
+    }
+}
+
+/// A [`DeserializeSeed`] that normalizes a path like [`normalize`] does,
+/// but also resolves relative paths against a base.
+///
+/// ```ignore
+/// let seed = NormalizeSeed::new(path!("/etc/app"));
+/// let path = seed.deserialize(&mut deserializer)?;
+/// ```
+///
+/// The path is stored in a [`CompactString`] by default. Use
+/// [`new_in`](Self::new_in) to pick another [`PathStorage`].
+pub struct NormalizeSeed<'base, S = CompactString> {
+    base: &'base AbsPath,
+    storage: PhantomData<S>,
+}
+
+impl<'base> NormalizeSeed<'base> {
+    /// Creates a new seed that resolves relative paths against the given
+    /// base.
+    #[inline]
+    pub fn new(base: &'base AbsPath) -> Self {
+        Self::new_in(base)
+    }
+}
+
+impl<'base, S: PathStorage> NormalizeSeed<'base, S> {
+    /// Same as [`new`](NormalizeSeed::new), but for any [`PathStorage`].
+    #[inline]
+    pub fn new_in(base: &'base AbsPath) -> Self {
+        Self { base, storage: PhantomData }
+    }
+}
+
+impl<S> Clone for NormalizeSeed<'_, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for NormalizeSeed<'_, S> {}
+
+impl<S> fmt::Debug for NormalizeSeed<'_, S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NormalizeSeed").field("base", &self.base).finish()
+    }
+}
+
+impl<'de, S: PathStorage> DeserializeSeed<'de> for NormalizeSeed<'_, S> {
+    type Value = GenericAbsPathBuf<S>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(NormalizeVisitor {
+            base: Some(self.base),
+            storage: PhantomData,
+        })
+    }
+}
+
+struct NormalizeVisitor<'base, S> {
+    base: Option<&'base AbsPath>,
+    storage: PhantomData<S>,
+}
+
+impl<S: PathStorage> Visitor<'_> for NormalizeVisitor<'_, S> {
+    type Value = GenericAbsPathBuf<S>;
+
+    #[inline]
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.base {
+            Some(_) => f.write_str("a path"),
+            None => f.write_str("an absolute path"),
+        }
+    }
+
+    #[inline]
+    fn visit_str<E: de::Error>(self, str: &str) -> Result<Self::Value, E> {
+        let joined;
+
+        let (to_normalize, prefix_len) = match self.base {
+            Some(base) if !str.starts_with(MAIN_SEPARATOR_STR) => {
+                joined = [base.as_str(), MAIN_SEPARATOR_STR, str].concat();
+                (&*joined, joined.len() - str.len())
+            },
+            _ => (str, 0),
+        };
+
+        match AbsPath::normalize(to_normalize) {
+            Ok(path) => Ok(GenericAbsPathBuf::from(&*path)),
+            Err(err) => {
+                // Make the offsets relative to the input.
+                let err = match err {
+                    NormalizeError::InvalidCharacter { byte_offset, ch } => {
+                        NormalizeError::InvalidCharacter {
+                            byte_offset: byte_offset - prefix_len,
+                            ch,
+                        }
+                    },
+                    other => other,
+                };
+                Err(E::custom(format_args!("invalid path {str:?}: {err}")))
+            },
+        }
+    }
+}
+
+/// A [`DeserializeSeed`] that validates a component and pushes it to a
+/// path, without allocating a string for it.
+struct PushComponent<'a, S>(&'a mut GenericAbsPathBuf<S>);
+
+/// Displays the components of a path separated by `/`, without a leading
+/// separator.
+struct JoinComponents<'a>(&'a AbsPath);
+
+/// Pushes the `/`-separated components in the given string to the path.
+#[inline]
+fn push_components<S: PathStorage, E: de::Error>(
+    path: &mut GenericAbsPathBuf<S>,
+    str: &str,
+) -> Result<(), E> {
+    str.split('/').try_for_each(|component| push_component(path, component))
+}
+
+#[inline]
+fn push_component<S: PathStorage, E: de::Error>(
+    path: &mut GenericAbsPathBuf<S>,
+    component: &str,
+) -> Result<(), E> {
+    let name = <&NodeName>::try_from(component).map_err(|err| {
+        E::custom(format_args!("invalid component {component:?}: {err}"))
+    })?;
+    path.push(name);
+    Ok(())
+}
+
+impl<'de, S: PathStorage> DeserializeSeed<'de> for PushComponent<'_, S> {
+    type Value = ();
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<S: PathStorage> Visitor<'_> for PushComponent<'_, S> {
+    type Value = ();
+
+    #[inline]
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a path component")
+    }
+
+    #[inline]
+    fn visit_str<E: de::Error>(self, str: &str) -> Result<(), E> {
+        push_component(self.0, str)
+    }
+}
+
+impl fmt::Display for JoinComponents<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, component) in self.0.components().enumerate() {
+            if idx > 0 {
+                f.write_str("/")?;
+            }
+            f.write_str(component.as_str())?;
+        }
+        Ok(())
+    }
+}
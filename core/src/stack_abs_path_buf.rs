@@ -0,0 +1,297 @@
+#[cfg(feature = "alloc")]
+use alloc::borrow::ToOwned;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+#[cfg(feature = "alloc")]
+use crate::AbsPathBuf;
+use crate::inline_str::InlineStr;
+use crate::{AbsPath, CapacityError, MAIN_SEPARATOR_STR, NodeName};
+
+/// An owned absolute path stored inline in a buffer of `N` bytes, which
+/// doesn't need an allocator.
+///
+/// It has the same API as [`AbsPathBuf`](crate::AbsPathBuf), except that
+/// every method that grows the path returns a [`CapacityError`] if the
+/// result doesn't fit in the buffer, in which case the path is left
+/// unchanged.
+///
+/// Every method is a `const fn`, so paths can also be built entirely at
+/// compile time, e.g. to compute tables of paths from a few constant
+/// components. The `*_const` variants of the methods that grow the path
+/// panic instead of returning an error, which in a `const` context becomes
+/// a compilation error.
+///
+/// ```ignore
+/// const ROOT: &AbsPath = path!("/srv/app");
+/// const LOGS: StackAbsPathBuf<32> =
+///     StackAbsPathBuf::new_const(ROOT).join_const(node!("logs"));
+/// ```
+#[derive(Copy, Clone)]
+pub struct StackAbsPathBuf<const N: usize> {
+    /// This is always a valid absolute path.
+    inner: InlineStr<N>,
+}
+
+impl<const N: usize> StackAbsPathBuf<N> {
+    /// Returns the path as an [`AbsPath`].
+    #[inline]
+    pub const fn as_path(&self) -> &AbsPath {
+        // SAFETY: the inner string is always a valid absolute path.
+        unsafe { AbsPath::from_str_unchecked(self.inner.as_str()) }
+    }
+
+    /// Returns the path as a string slice.
+    #[inline]
+    pub const fn as_str(&self) -> &str {
+        self.inner.as_str()
+    }
+
+    /// Returns the maximum length of the path in bytes, i.e. `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Appends every component of `other` to the path.
+    #[inline]
+    pub const fn concat(
+        mut self,
+        other: &AbsPath,
+    ) -> Result<Self, CapacityError> {
+        match self.concat_mut(other) {
+            Ok(_) => Ok(self),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Like [`concat`](Self::concat), but panics if the resulting path
+    /// doesn't fit in the buffer.
+    #[inline]
+    pub const fn concat_const(self, other: &AbsPath) -> Self {
+        unwrap(self.concat(other))
+    }
+
+    /// Appends every component of `other` to the path in place.
+    #[inline]
+    pub const fn concat_mut(
+        &mut self,
+        other: &AbsPath,
+    ) -> Result<&mut Self, CapacityError> {
+        if other.is_root() {
+            return Ok(self);
+        }
+        let res = if self.as_path().is_root() {
+            match Self::new(other) {
+                Ok(new) => {
+                    *self = new;
+                    Ok(())
+                },
+                Err(err) => Err(err),
+            }
+        } else {
+            self.inner.push_strs(&[other.as_str()])
+        };
+        match res {
+            Ok(()) => Ok(self),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Appends the given node name to the path.
+    #[inline]
+    pub const fn join(
+        mut self,
+        node_name: &NodeName,
+    ) -> Result<Self, CapacityError> {
+        match self.push(node_name) {
+            Ok(_) => Ok(self),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Like [`join`](Self::join), but panics if the resulting path doesn't
+    /// fit in the buffer.
+    #[inline]
+    pub const fn join_const(self, node_name: &NodeName) -> Self {
+        unwrap(self.join(node_name))
+    }
+
+    /// Creates a new buffer containing the given path.
+    #[inline]
+    pub const fn new(path: &AbsPath) -> Result<Self, CapacityError> {
+        let mut inner = InlineStr::new();
+        match inner.push_strs(&[path.as_str()]) {
+            Ok(()) => Ok(Self { inner }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Like [`new`](Self::new), but panics if the path doesn't fit in the
+    /// buffer.
+    #[inline]
+    pub const fn new_const(path: &AbsPath) -> Self {
+        unwrap(Self::new(path))
+    }
+
+    /// Removes the last component of the path, returning `false` if the path
+    /// is the root.
+    #[inline]
+    pub const fn pop(&mut self) -> bool {
+        match self.as_path().parent() {
+            Some(parent) => {
+                self.inner.truncate(parent.as_str().len());
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Appends the given node name to the path.
+    #[inline]
+    pub const fn push(
+        &mut self,
+        node_name: &NodeName,
+    ) -> Result<&mut Self, CapacityError> {
+        let node_name = node_name.as_str();
+        let res = if self.as_path().is_root() {
+            self.inner.push_strs(&[node_name])
+        } else {
+            self.inner.push_strs(&[MAIN_SEPARATOR_STR, node_name])
+        };
+        match res {
+            Ok(()) => Ok(self),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Creates a new buffer containing the root path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is less than the length of the path separator.
+    #[inline]
+    pub const fn root() -> Self {
+        Self::new_const(AbsPath::root())
+    }
+}
+
+/// Unwraps the result in a `const` context.
+#[inline]
+const fn unwrap<const N: usize>(
+    res: Result<StackAbsPathBuf<N>, CapacityError>,
+) -> StackAbsPathBuf<N> {
+    match res {
+        Ok(path) => path,
+        Err(_) => panic!("path exceeds the capacity of the StackAbsPathBuf"),
+    }
+}
+
+impl<const N: usize> Deref for StackAbsPathBuf<N> {
+    type Target = AbsPath;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_path()
+    }
+}
+
+impl<const N: usize> Borrow<AbsPath> for StackAbsPathBuf<N> {
+    #[inline]
+    fn borrow(&self) -> &AbsPath {
+        self.as_path()
+    }
+}
+
+impl<const N: usize> AsRef<AbsPath> for StackAbsPathBuf<N> {
+    #[inline]
+    fn as_ref(&self) -> &AbsPath {
+        self.as_path()
+    }
+}
+
+impl<const N: usize> AsRef<str> for StackAbsPathBuf<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Debug for StackAbsPathBuf<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_path(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for StackAbsPathBuf<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_path(), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> From<StackAbsPathBuf<N>> for AbsPathBuf {
+    #[inline]
+    fn from(path: StackAbsPathBuf<N>) -> Self {
+        path.as_path().to_owned()
+    }
+}
+
+impl<const N: usize> Hash for StackAbsPathBuf<N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_path().hash(state);
+    }
+}
+
+impl<const N: usize> TryFrom<&AbsPath> for StackAbsPathBuf<N> {
+    type Error = CapacityError;
+
+    #[inline]
+    fn try_from(path: &AbsPath) -> Result<Self, Self::Error> {
+        Self::new(path)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<StackAbsPathBuf<M>>
+    for StackAbsPathBuf<N>
+{
+    #[inline]
+    fn eq(&self, other: &StackAbsPathBuf<M>) -> bool {
+        self.as_path() == other.as_path()
+    }
+}
+
+impl<const N: usize> Eq for StackAbsPathBuf<N> {}
+
+impl<const N: usize> PartialEq<AbsPath> for StackAbsPathBuf<N> {
+    #[inline]
+    fn eq(&self, other: &AbsPath) -> bool {
+        self.as_path() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&AbsPath> for StackAbsPathBuf<N> {
+    #[inline]
+    fn eq(&self, other: &&AbsPath) -> bool {
+        self.as_path() == *other
+    }
+}
+
+impl<const N: usize> PartialEq<str> for StackAbsPathBuf<N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for StackAbsPathBuf<N> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
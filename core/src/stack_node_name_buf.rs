@@ -0,0 +1,157 @@
+#[cfg(feature = "alloc")]
+use alloc::borrow::ToOwned;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+#[cfg(feature = "alloc")]
+use crate::NodeNameBuf;
+use crate::inline_str::InlineStr;
+use crate::{CapacityError, NodeName};
+
+/// An owned file name stored inline in a buffer of `N` bytes, which doesn't
+/// need an allocator.
+#[derive(Copy, Clone)]
+pub struct StackNodeNameBuf<const N: usize> {
+    /// This is always a valid file name.
+    inner: InlineStr<N>,
+}
+
+impl<const N: usize> StackNodeNameBuf<N> {
+    /// Returns the file name as a [`NodeName`].
+    #[inline]
+    pub const fn as_name(&self) -> &NodeName {
+        // SAFETY: the inner string is always a valid file name.
+        unsafe { NodeName::from_str_unchecked(self.inner.as_str()) }
+    }
+
+    /// Returns the file name as a string slice.
+    #[inline]
+    pub const fn as_str(&self) -> &str {
+        self.inner.as_str()
+    }
+
+    /// Returns the maximum length of the file name in bytes, i.e. `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Creates a new buffer containing the given file name.
+    #[inline]
+    pub const fn new(name: &NodeName) -> Result<Self, CapacityError> {
+        let mut inner = InlineStr::new();
+        match inner.push_strs(&[name.as_str()]) {
+            Ok(()) => Ok(Self { inner }),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<const N: usize> Deref for StackNodeNameBuf<N> {
+    type Target = NodeName;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_name()
+    }
+}
+
+impl<const N: usize> Borrow<NodeName> for StackNodeNameBuf<N> {
+    #[inline]
+    fn borrow(&self) -> &NodeName {
+        self.as_name()
+    }
+}
+
+impl<const N: usize> AsRef<NodeName> for StackNodeNameBuf<N> {
+    #[inline]
+    fn as_ref(&self) -> &NodeName {
+        self.as_name()
+    }
+}
+
+impl<const N: usize> AsRef<str> for StackNodeNameBuf<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Debug for StackNodeNameBuf<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_name(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for StackNodeNameBuf<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_name(), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> From<StackNodeNameBuf<N>> for NodeNameBuf {
+    #[inline]
+    fn from(name: StackNodeNameBuf<N>) -> Self {
+        name.as_name().to_owned()
+    }
+}
+
+impl<const N: usize> Hash for StackNodeNameBuf<N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_name().hash(state);
+    }
+}
+
+impl<const N: usize> TryFrom<&NodeName> for StackNodeNameBuf<N> {
+    type Error = CapacityError;
+
+    #[inline]
+    fn try_from(name: &NodeName) -> Result<Self, Self::Error> {
+        Self::new(name)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<StackNodeNameBuf<M>>
+    for StackNodeNameBuf<N>
+{
+    #[inline]
+    fn eq(&self, other: &StackNodeNameBuf<M>) -> bool {
+        self.as_name() == other.as_name()
+    }
+}
+
+impl<const N: usize> Eq for StackNodeNameBuf<N> {}
+
+impl<const N: usize> PartialEq<NodeName> for StackNodeNameBuf<N> {
+    #[inline]
+    fn eq(&self, other: &NodeName) -> bool {
+        self.as_name() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&NodeName> for StackNodeNameBuf<N> {
+    #[inline]
+    fn eq(&self, other: &&NodeName) -> bool {
+        self.as_name() == *other
+    }
+}
+
+impl<const N: usize> PartialEq<str> for StackNodeNameBuf<N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for StackNodeNameBuf<N> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
@@ -0,0 +1,186 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use unicode_normalization::{
+    IsNormalized,
+    UnicodeNormalization,
+    is_nfc_quick,
+    is_nfd_quick,
+};
+
+use crate::{AbsPath, AbsPathBuf, NodeName, NodeNameBuf, scan};
+
+/// A wrapper around a path or a file name whose [`Eq`] and [`Hash`] impls
+/// compare the [NFC] forms of the wrapped strings.
+///
+/// This is useful when paths can come from different sources, e.g. macOS
+/// returns file names in NFD while most keyboards produce NFC, so two names
+/// that look identical can be made up of different code points.
+///
+/// [NFC]: https://unicode.org/reports/tr15/
+#[derive(Copy, Clone)]
+pub struct NfcEq<T>(pub T);
+
+#[derive(Copy, Clone)]
+enum Form {
+    Nfc,
+    Nfd,
+}
+
+impl AbsPath {
+    /// Returns the path with every component converted to [NFC].
+    ///
+    /// The path is only allocated if at least one of its components is not
+    /// already in NFC. Separators are never touched.
+    ///
+    /// Components whose NFC form wouldn't be a valid file name on the
+    /// current platform are left unchanged.
+    ///
+    /// [NFC]: https://unicode.org/reports/tr15/
+    #[inline]
+    pub fn to_nfc(&self) -> Cow<'_, Self> {
+        Form::Nfc.normalize_path(self)
+    }
+
+    /// Returns the path with every component converted to [NFD].
+    ///
+    /// The path is only allocated if at least one of its components is not
+    /// already in NFD. Separators are never touched.
+    ///
+    /// Components whose NFD form wouldn't be a valid file name on the
+    /// current platform are left unchanged.
+    ///
+    /// [NFD]: https://unicode.org/reports/tr15/
+    #[inline]
+    pub fn to_nfd(&self) -> Cow<'_, Self> {
+        Form::Nfd.normalize_path(self)
+    }
+}
+
+impl NodeName {
+    /// Returns the file name converted to [NFC], allocating only if it's not
+    /// already in that form.
+    ///
+    /// The name is returned unchanged if its NFC form wouldn't be a valid
+    /// file name on the current platform.
+    ///
+    /// [NFC]: https://unicode.org/reports/tr15/
+    #[inline]
+    pub fn to_nfc(&self) -> Cow<'_, Self> {
+        Form::Nfc.normalize_node_name(self)
+    }
+
+    /// Returns the file name converted to [NFD], allocating only if it's not
+    /// already in that form.
+    ///
+    /// The name is returned unchanged if its NFD form wouldn't be a valid
+    /// file name on the current platform.
+    ///
+    /// [NFD]: https://unicode.org/reports/tr15/
+    #[inline]
+    pub fn to_nfd(&self) -> Cow<'_, Self> {
+        Form::Nfd.normalize_node_name(self)
+    }
+}
+
+impl Form {
+    #[inline]
+    fn is_normalized(self, str: &str) -> bool {
+        let quick_check = match self {
+            Self::Nfc => is_nfc_quick(str.chars()),
+            Self::Nfd => is_nfd_quick(str.chars()),
+        };
+        match quick_check {
+            IsNormalized::Yes => true,
+            IsNormalized::No => false,
+            IsNormalized::Maybe => match self {
+                Self::Nfc => str.chars().eq(str.nfc()),
+                Self::Nfd => str.chars().eq(str.nfd()),
+            },
+        }
+    }
+
+    #[inline]
+    fn normalize(self, str: &str) -> String {
+        match self {
+            Self::Nfc => str.nfc().collect(),
+            Self::Nfd => str.nfd().collect(),
+        }
+    }
+
+    #[inline]
+    fn normalize_node_name(self, name: &NodeName) -> Cow<'_, NodeName> {
+        if self.is_normalized(name) {
+            return Cow::Borrowed(name);
+        }
+        let normalized = self.normalize(name);
+        // Decompositions can produce characters that are invalid on some
+        // platforms, e.g. `≮` decomposes into `<` followed by a combining
+        // mark, so the name is kept as-is if it's no longer valid.
+        match scan::node_name_from_str(&normalized) {
+            Ok(_) => Cow::Owned(NodeNameBuf::new(normalized.into())),
+            Err(_) => Cow::Borrowed(name),
+        }
+    }
+
+    #[inline]
+    fn normalize_path(self, path: &AbsPath) -> Cow<'_, AbsPath> {
+        if path.components().all(|component| self.is_normalized(component)) {
+            return Cow::Borrowed(path);
+        }
+        let mut normalized = AbsPathBuf::root();
+        for component in path.components() {
+            normalized.push(self.normalize_node_name(component));
+        }
+        Cow::Owned(normalized)
+    }
+}
+
+macro_rules! impl_nfc_eq {
+    ([$($lt:lifetime)?] $ty:ty) => {
+        // Normalizing the whole string is equivalent to normalizing each
+        // component on its own, because the separator is a starter that
+        // never composes with its neighbors.
+        impl<$($lt)?> PartialEq for NfcEq<$ty> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                let (lhs, rhs) = (self.0.as_str(), other.0.as_str());
+                lhs == rhs || lhs.nfc().eq(rhs.nfc())
+            }
+        }
+
+        impl<$($lt)?> Eq for NfcEq<$ty> {}
+
+        impl<$($lt)?> Hash for NfcEq<$ty> {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                for ch in self.0.as_str().nfc() {
+                    state.write_u32(ch as u32);
+                }
+                // Same as `str`'s impl, to make the encoding prefix-free.
+                state.write_u8(0xff);
+            }
+        }
+
+        impl<$($lt)?> fmt::Debug for NfcEq<$ty> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_tuple("NfcEq").field(&self.0).finish()
+            }
+        }
+
+        impl<$($lt)?> fmt::Display for NfcEq<$ty> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+    };
+}
+
+impl_nfc_eq!(['a] &'a AbsPath);
+impl_nfc_eq!([] AbsPathBuf);
+impl_nfc_eq!(['a] &'a NodeName);
+impl_nfc_eq!([] NodeNameBuf);
@@ -0,0 +1,374 @@
+use core::ops::Range;
+
+use abs_path_core::{AbsPath, InvalidAbsPathError, InvalidNodeNameError};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::LitStr;
+
+/// Returns the error for a string literal that's not a valid absolute path.
+///
+/// The offsets in `err` are relative to the literal's value with `prefix`
+/// prepended to it, which is used to validate relative paths by turning them
+/// into absolute ones.
+pub(crate) fn invalid_abs_path(
+    lit: &LitStr,
+    prefix: &str,
+    err: InvalidAbsPathError,
+) -> syn::Error {
+    let value = format!("{prefix}{}", lit.value());
+    let separator = AbsPath::root().as_str();
+
+    let (range, msg, help) = match err {
+        InvalidAbsPathError::NotAbsolute => (
+            0..value.chars().next().map_or(0, char::len_utf8),
+            "path is not absolute".to_owned(),
+            format!("add a leading `{separator}`"),
+        ),
+        InvalidAbsPathError::EmptyComponent { byte_offset, .. } => {
+            let is_trailing = byte_offset == value.len();
+            (
+                byte_offset - separator.len()..byte_offset,
+                if is_trailing {
+                    "path ends with a separator".to_owned()
+                } else {
+                    "path contains consecutive separators".to_owned()
+                },
+                format!("remove the extra `{separator}`"),
+            )
+        },
+        InvalidAbsPathError::SingleDotComponent { byte_offset, .. } => (
+            byte_offset..byte_offset + 1,
+            "path contains a `.` component".to_owned(),
+            "remove the component, or use `AbsPath::normalize()` to resolve \
+             it at runtime"
+                .to_owned(),
+        ),
+        InvalidAbsPathError::DoubleDotComponent { byte_offset, .. } => (
+            byte_offset..byte_offset + 2,
+            "path contains a `..` component".to_owned(),
+            "remove the component together with the one before it, or use \
+             `AbsPath::normalize()` to resolve it at runtime"
+                .to_owned(),
+        ),
+        InvalidAbsPathError::InvalidCharacter { byte_offset, ch, .. } => {
+            let (_, component) = component_at(&value, byte_offset, separator);
+            (
+                byte_offset..byte_offset + ch.len_utf8(),
+                format!(
+                    "path component {component:?} contains the invalid \
+                     character {ch:?}"
+                ),
+                format!("file names can't contain {ch:?} on this platform"),
+            )
+        },
+    };
+
+    // Every error but the separator ones points inside a single component.
+    let component = match err {
+        InvalidAbsPathError::NotAbsolute
+        | InvalidAbsPathError::EmptyComponent { .. } => None,
+        _ => Some(component_at(&value, range.start, separator)),
+    };
+
+    let range = range.start.saturating_sub(prefix.len())
+        ..range.end.saturating_sub(prefix.len());
+
+    error(lit, range, component, &msg, &help)
+}
+
+/// Returns the error for a string literal that's not a valid node name.
+pub(crate) fn invalid_node_name(
+    lit: &LitStr,
+    err: InvalidNodeNameError,
+) -> syn::Error {
+    let value = lit.value();
+    let separator = AbsPath::root().as_str();
+
+    let (range, help) = match err {
+        InvalidNodeNameError::ContainsInvalidCharacter(ch) => {
+            let offset = value.find(ch).unwrap_or(0);
+            let help = if value.contains(separator) {
+                "use `path!()` to create paths with multiple components"
+                    .to_owned()
+            } else {
+                format!("file names can't contain {ch:?} on this platform")
+            };
+            (offset..offset + ch.len_utf8(), help)
+        },
+        InvalidNodeNameError::Empty => {
+            (0..0, "file names must contain at least one character".into())
+        },
+        InvalidNodeNameError::SingleDot | InvalidNodeNameError::DoubleDot => (
+            0..value.len(),
+            "`.` and `..` refer to directories relative to another path, so \
+             they're not valid file names"
+                .into(),
+        ),
+        // `NodeName::from_str` doesn't check portability profiles.
+        InvalidNodeNameError::NotPortable(_) => {
+            (0..value.len(), String::new())
+        },
+    };
+
+    error(lit, range, None, &err.to_string(), &help)
+}
+
+/// Turns the error into tokens that can be used in expression position.
+///
+/// Unlike [`syn::Error::into_compile_error`], this also works for errors
+/// combined with their help message, which expand to more than one
+/// `compile_error!` invocation.
+pub(crate) fn into_compile_error(err: syn::Error) -> TokenStream {
+    let errors = err.into_compile_error();
+    quote! {{ #errors }}
+}
+
+/// Returns the 1-based index and the contents of the component of the
+/// absolute `path` containing the given byte offset.
+fn component_at<'a>(
+    path: &'a str,
+    offset: usize,
+    separator: &str,
+) -> (usize, &'a str) {
+    let index = path[..offset].matches(separator).count();
+    let start = path[..offset].rfind(separator).map_or(0, |idx| idx + 1);
+    let end =
+        path[offset..].find(separator).map_or(path.len(), |idx| offset + idx);
+    (index, &path[start..end])
+}
+
+/// Returns an error pointing at the given byte range of the literal's value,
+/// followed by a separate error with the help message, if any.
+///
+/// The errors can only point at the exact range on nightly compilers, which
+/// support sub-spans of literals. Everywhere else they point at the whole
+/// literal, and the message describes the range instead, together with the
+/// failing component if there is one.
+fn error(
+    lit: &LitStr,
+    range: Range<usize>,
+    component: Option<(usize, &str)>,
+    msg: &str,
+    help: &str,
+) -> syn::Error {
+    let (span, msg) = match subspan(lit, range.clone()) {
+        Some(span) => (span, msg.to_owned()),
+        None if range.is_empty() || range == (0..lit.value().len()) => {
+            (lit.span(), msg.to_owned())
+        },
+        None => {
+            let Range { start, end } = range;
+            let location = match component {
+                Some((index, component)) => {
+                    let component = component.escape_debug();
+                    format!("component {index} (`{component}`) at bytes")
+                },
+                None => "bytes".to_owned(),
+            };
+            (lit.span(), format!("{msg}: {location} {start}..{end}"))
+        },
+    };
+
+    let mut err = syn::Error::new(span, msg);
+    if !help.is_empty() {
+        err.combine(syn::Error::new(span, format!("help: {help}")));
+    }
+    err
+}
+
+/// Returns the span of the given byte range of the literal's value, if it
+/// can be computed.
+///
+/// Sub-spans are only available on nightly compilers.
+fn subspan(lit: &LitStr, range: Range<usize>) -> Option<Span> {
+    let token = lit.token();
+
+    source_range(&token.to_string(), &lit.value(), range)
+        .and_then(|range| token.subspan(range))
+}
+
+/// Maps a byte range of a string literal's value to the corresponding byte
+/// range of its source code, quotes included.
+///
+/// Returns `None` if the range is empty, or if the literal is not a plain
+/// string without escapes, since only then every byte of the value maps to a
+/// byte of the source code.
+fn source_range(
+    source: &str,
+    value: &str,
+    range: Range<usize>,
+) -> Option<Range<usize>> {
+    let contents = source.strip_prefix('"')?.strip_suffix('"')?;
+
+    if range.is_empty() || range.end > value.len() || contents != value {
+        return None;
+    }
+
+    // Skip the opening quote.
+    Some(range.start + 1..range.end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use abs_path_core::{AbsPath, NodeName};
+    use proc_macro2::Span;
+    use syn::LitStr;
+
+    use super::*;
+
+    fn abs_path_error(value: &str) -> Vec<String> {
+        let lit = LitStr::new(value, Span::call_site());
+        let err = AbsPath::from_str(value).expect_err("path is valid");
+        invalid_abs_path(&lit, "", err)
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    fn relative_path_error(value: &str) -> Vec<String> {
+        let lit = LitStr::new(value, Span::call_site());
+        let root = AbsPath::root().as_str();
+        let err = AbsPath::from_str(&format!("{root}{value}"))
+            .expect_err("path is valid");
+        invalid_abs_path(&lit, root, err)
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    fn node_name_error(value: &str) -> Vec<String> {
+        let lit = LitStr::new(value, Span::call_site());
+        let err = NodeName::from_str(value).expect_err("file name is valid");
+        invalid_node_name(&lit, err)
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn source_range_plain() {
+        assert_eq!(source_range(r#""/foo/..""#, "/foo/..", 5..7), Some(6..8));
+        assert_eq!(source_range(r#""/é/..""#, "/é/..", 4..6), Some(5..7));
+    }
+
+    #[test]
+    fn source_range_empty() {
+        assert_eq!(source_range(r#""""#, "", 0..0), None);
+        assert_eq!(source_range(r#""/foo""#, "/foo", 2..2), None);
+    }
+
+    #[test]
+    fn source_range_escapes() {
+        // The escapes make the source longer than the value, so offsets in
+        // the value don't point at the same characters in the source.
+        assert_eq!(source_range(r#""/f\u{6f}o""#, "/foo", 1..2), None);
+        assert_eq!(source_range(r#""/a\\b""#, r"/a\b", 2..3), None);
+    }
+
+    #[test]
+    fn source_range_raw_and_suffixed() {
+        assert_eq!(source_range(r##"r"/foo""##, "/foo", 1..2), None);
+        assert_eq!(source_range(r#""/foo"suffix"#, "/foo", 1..2), None);
+    }
+
+    #[test]
+    fn source_range_out_of_bounds() {
+        assert_eq!(source_range(r#""/foo""#, "/foo", 3..5), None);
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "windows", ignore)]
+    fn abs_path_messages() {
+        assert_eq!(
+            abs_path_error("/foo/../bar"),
+            [
+                "path contains a `..` component: component 2 (`..`) at bytes \
+                 5..7",
+                "help: remove the component together with the one before it, \
+                 or use `AbsPath::normalize()` to resolve it at runtime",
+            ]
+        );
+        assert_eq!(
+            abs_path_error("/foo/./bar"),
+            [
+                "path contains a `.` component: component 2 (`.`) at bytes \
+                 5..6",
+                "help: remove the component, or use `AbsPath::normalize()` \
+                 to resolve it at runtime",
+            ]
+        );
+        assert_eq!(
+            abs_path_error("/foo/"),
+            [
+                "path ends with a separator: bytes 4..5",
+                "help: remove the extra `/`",
+            ]
+        );
+        assert_eq!(
+            abs_path_error("/foo//bar"),
+            [
+                "path contains consecutive separators: bytes 4..5",
+                "help: remove the extra `/`",
+            ]
+        );
+        assert_eq!(
+            abs_path_error("foo"),
+            ["path is not absolute: bytes 0..1", "help: add a leading `/`",]
+        );
+        assert_eq!(
+            abs_path_error("/foo/b\0r"),
+            [
+                r#"path component "b\0r" contains the invalid character '\0': component 2 (`b\0r`) at bytes 6..7"#,
+                r"help: file names can't contain '\0' on this platform",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "windows", ignore)]
+    fn relative_path_messages() {
+        assert_eq!(
+            relative_path_error("foo/../bar"),
+            [
+                "path contains a `..` component: component 2 (`..`) at bytes \
+                 4..6",
+                "help: remove the component together with the one before it, \
+                 or use `AbsPath::normalize()` to resolve it at runtime",
+            ]
+        );
+        assert_eq!(
+            relative_path_error("foo/"),
+            [
+                "path ends with a separator: bytes 3..4",
+                "help: remove the extra `/`",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "windows", ignore)]
+    fn node_name_messages() {
+        assert_eq!(
+            node_name_error("foo/bar"),
+            [
+                "file name contains an invalid character: '/': bytes 3..4",
+                "help: use `path!()` to create paths with multiple components",
+            ]
+        );
+        assert_eq!(
+            node_name_error(""),
+            [
+                "file name is empty",
+                "help: file names must contain at least one character",
+            ]
+        );
+        assert_eq!(
+            node_name_error(".."),
+            [
+                "file name is a double dot (`..`)",
+                "help: `.` and `..` refer to directories relative to another \
+                 path, so they're not valid file names",
+            ]
+        );
+    }
+}
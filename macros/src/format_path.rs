@@ -0,0 +1,219 @@
+use abs_path_core::{AbsPath, NodeName};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, Ident, LitStr, Token};
+
+/// The input of the `format_path!` macro, i.e. a template literal followed
+/// by optional `name = value` arguments.
+pub(crate) struct FormatPathInput {
+    template: LitStr,
+    args: Vec<(Ident, Expr)>,
+}
+
+/// A component of the template, made up of fixed text and placeholders.
+type Component = Vec<Piece>;
+
+enum Piece {
+    Fixed(String),
+    Placeholder { name: String, spec: Option<String> },
+}
+
+impl FormatPathInput {
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        let components = parse_template(&self.template)?;
+
+        let builder = Ident::new("builder", Span::mixed_site());
+
+        let mut used_args = vec![false; self.args.len()];
+        let mut statements = Vec::new();
+
+        for component in &components {
+            for piece in component {
+                match piece {
+                    Piece::Fixed(fixed) => statements.push(quote! {
+                        #builder.push_str(#fixed);
+                    }),
+                    Piece::Placeholder { name, spec } => {
+                        let value = match self
+                            .args
+                            .iter()
+                            .position(|(arg_name, _)| arg_name == name)
+                        {
+                            Some(idx) => {
+                                used_args[idx] = true;
+                                arg_binding(idx)
+                            },
+                            // Implicitly capture a variable with the same
+                            // name, like `format!()` does.
+                            None => Ident::new(name, self.template.span()),
+                        };
+                        let format_str = LitStr::new(
+                            &format!("{{:{}}}", spec.as_deref().unwrap_or("")),
+                            self.template.span(),
+                        );
+                        statements.push(quote! {
+                            #builder.push_value(
+                                #name,
+                                ::core::format_args!(#format_str, #value),
+                            );
+                        });
+                    },
+                }
+            }
+            statements.push(quote! { #builder.end_component(); });
+        }
+
+        if let Some(idx) = used_args.iter().position(|&used| !used) {
+            let (name, _) = &self.args[idx];
+            return Err(syn::Error::new_spanned(
+                name,
+                "named argument is never used in the template",
+            ));
+        }
+
+        let arg_bindings = (0..self.args.len()).map(arg_binding);
+        let arg_exprs = self.args.iter().map(|(_, expr)| expr);
+
+        // Evaluate every argument exactly once, in order, before formatting.
+        Ok(quote! {
+            match (#(&(#arg_exprs),)*) {
+                (#(#arg_bindings,)*) => {
+                    let mut #builder =
+                        ::abs_path::__private::FormatPath::default();
+                    #(#statements)*
+                    #builder.finish()
+                }
+            }
+        })
+    }
+}
+
+fn arg_binding(idx: usize) -> Ident {
+    format_ident!("arg_{}", idx, span = Span::mixed_site())
+}
+
+fn parse_template(template: &LitStr) -> syn::Result<Vec<Component>> {
+    let error = |msg: &str| syn::Error::new_spanned(template, msg);
+
+    let str = template.value();
+    let separator = AbsPath::root().as_str();
+
+    let Some(rest) = str.strip_prefix(separator) else {
+        return Err(error("template must start with a path separator"));
+    };
+
+    let mut components = Vec::new();
+
+    if rest.is_empty() {
+        return Ok(components);
+    }
+
+    for component_str in rest.split(separator) {
+        let component = parse_component(component_str).map_err(error)?;
+        validate_component(&component).map_err(error)?;
+        components.push(component);
+    }
+
+    Ok(components)
+}
+
+fn parse_component(str: &str) -> Result<Component, &'static str> {
+    let mut pieces = Vec::new();
+    let mut fixed = String::new();
+    let mut chars = str.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                fixed.push('{');
+            },
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                fixed.push('}');
+            },
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => placeholder.push(ch),
+                        None => return Err("unterminated placeholder"),
+                    }
+                }
+                let (name, spec) = match placeholder.split_once(':') {
+                    Some((name, spec)) => (name, Some(spec.to_owned())),
+                    None => (&*placeholder, None),
+                };
+                if name.is_empty() {
+                    return Err("placeholders must be named, e.g. `{name}`");
+                }
+                if syn::parse_str::<Ident>(name).is_err() {
+                    return Err("placeholder names must be identifiers");
+                }
+                if !fixed.is_empty() {
+                    pieces.push(Piece::Fixed(core::mem::take(&mut fixed)));
+                }
+                pieces
+                    .push(Piece::Placeholder { name: name.to_owned(), spec });
+            },
+            '}' => return Err("unmatched `}` in template"),
+            ch => fixed.push(ch),
+        }
+    }
+
+    if !fixed.is_empty() {
+        pieces.push(Piece::Fixed(fixed));
+    }
+
+    Ok(pieces)
+}
+
+fn validate_component(component: &Component) -> Result<(), &'static str> {
+    let has_placeholders = component
+        .iter()
+        .any(|piece| matches!(piece, Piece::Placeholder { .. }));
+
+    if !has_placeholders {
+        let fixed = match component.as_slice() {
+            [Piece::Fixed(fixed)] => fixed.as_str(),
+            _ => "",
+        };
+        return NodeName::from_str(fixed)
+            .map(|_| ())
+            .map_err(|_| "template contains an invalid path component");
+    }
+
+    // Interpolated values are validated at runtime, and a valid value can't
+    // be turned into a `.` or `..` component by the surrounding text, so we
+    // only need to check the fixed parts for invalid characters. The `_`
+    // prefix is there to avoid rejecting pieces like `.` in `{name}.{ext}`.
+    for piece in component {
+        if let Piece::Fixed(fixed) = piece
+            && NodeName::from_str(&format!("_{fixed}")).is_err()
+        {
+            return Err("template contains an invalid character");
+        }
+    }
+
+    Ok(())
+}
+
+impl Parse for FormatPathInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let template = input.parse()?;
+        let mut args = Vec::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let name = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value = input.parse()?;
+            args.push((name, value));
+        }
+        Ok(Self { template, args })
+    }
+}
@@ -1,31 +1,272 @@
 //! .
 
+mod diagnostics;
+mod format_path;
+mod path_pattern;
+mod resolve_paths;
+
 use abs_path_core::{AbsPath, NodeName};
 use proc_macro::TokenStream;
-use syn::{LitStr, parse_macro_input};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{DeriveInput, Expr, ExprLit, Lit, LitStr, Token, parse_macro_input};
+
+/// Formats a path at runtime from a template whose placeholders are replaced
+/// with the given values, like `format!()`.
+///
+/// The template is parsed and its fixed parts are validated at compile time.
+/// At runtime every interpolated value is checked to be a valid `NodeName`,
+/// so the expansion evaluates to a `Result<AbsPathBuf, FormatPathError>`
+/// whose error names the placeholder with the invalid value. Placeholders
+/// must be named, and their values are either given as `name = value`
+/// arguments or captured from variables with the same name.
+///
+/// ```ignore
+/// let id = 42;
+/// let avatar = format_path!("/users/{id}/avatars/{name}.png", name = "me")?;
+/// ```
+#[proc_macro]
+pub fn format_path(input: TokenStream) -> TokenStream {
+    parse_macro_input!(input as format_path::FormatPathInput)
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
 /// TODO: docs.
 #[proc_macro]
 pub fn node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as LitStr);
     match <&NodeName>::try_from(&*input.value()) {
-        Ok(_) => quote::quote! {
+        Ok(_) => quote! {
             unsafe { ::abs_path::NodeName::from_str_unchecked(#input) }
         },
-        Err(err) => syn::Error::new_spanned(input, err).into_compile_error(),
+        Err(err) => diagnostics::into_compile_error(
+            diagnostics::invalid_node_name(&input, err),
+        ),
     }
     .into()
 }
 
-/// TODO: docs.
+/// Creates a `&'static AbsPath` from a sequence of parts, validating the
+/// string literals at compile time.
+///
+/// The parts can be either string literals or constant expressions of type
+/// `&NodeName`, like `node!` invocations or the names of `const` items. If
+/// the first part is a literal it must be an absolute path, while the
+/// literals after it must be relative paths, e.g. `"foo"` or `"foo/bar"`. If
+/// it's not a literal the path starts at the root.
+///
+/// The expansion is a constant expression, so it can be used to initialize
+/// `const`s and `static`s.
+///
+/// If a literal is invalid, the error points at the offending part of it on
+/// nightly compilers, since only those can compute the span of a part of a
+/// literal. On stable the error points at the whole literal, and its message
+/// names the offending component and its byte range instead.
+///
+/// ```ignore
+/// const APP: &NodeName = node!("my-app");
+/// const CONFIG: &AbsPath = path!("/etc", APP, "config.toml");
+/// ```
 #[proc_macro]
 pub fn path(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as LitStr);
-    match <&AbsPath>::try_from(&*input.value()) {
-        Ok(_) => quote::quote! {
-            unsafe { ::abs_path::AbsPath::from_str_unchecked(#input) }
-        },
-        Err(err) => syn::Error::new_spanned(input, err).into_compile_error(),
+    let parts = parse_macro_input!(input as PathParts);
+
+    // Keep the simple expansion for the common case.
+    if let [PathPart::Lit(lit)] = &*parts.0 {
+        return match <&AbsPath>::try_from(&*lit.value()) {
+            Ok(_) => quote! {
+                unsafe { ::abs_path::AbsPath::from_str_unchecked(#lit) }
+            },
+            Err(err) => diagnostics::into_compile_error(
+                diagnostics::invalid_abs_path(lit, "", err),
+            ),
+        }
+        .into();
     }
+
+    let mut strs = Vec::with_capacity(parts.0.len() + 1);
+
+    for (idx, part) in parts.0.iter().enumerate() {
+        match part {
+            PathPart::Lit(lit) if idx == 0 => {
+                if let Err(err) = <&AbsPath>::try_from(&*lit.value()) {
+                    return diagnostics::into_compile_error(
+                        diagnostics::invalid_abs_path(lit, "", err),
+                    )
+                    .into();
+                }
+                strs.push(quote! { #lit });
+            },
+            PathPart::Lit(lit) => {
+                if let Err(err) = validate_relative(lit) {
+                    return diagnostics::into_compile_error(err).into();
+                }
+                strs.push(quote! { #lit });
+            },
+            PathPart::Expr(expr) => {
+                if idx == 0 {
+                    strs.push(quote! {
+                        ::abs_path::AbsPath::root().as_str()
+                    });
+                }
+                strs.push(quote! {
+                    ::abs_path::NodeName::as_str(#expr)
+                });
+            },
+        }
+    }
+
+    quote! {{
+        const PATH: &'static ::abs_path::AbsPath = {
+            const PARTS: &[&str] = &[#(#strs),*];
+            const BYTES: [u8; ::abs_path::__private::path_len(PARTS)] =
+                ::abs_path::__private::path_concat(PARTS);
+            // SAFETY: the first part is an absolute path, and the others are
+            // either valid node names or relative paths validated above.
+            unsafe {
+                ::abs_path::AbsPath::from_str_unchecked(
+                    ::core::str::from_utf8_unchecked(&BYTES),
+                )
+            }
+        };
+        PATH
+    }}
     .into()
 }
+
+/// The comma-separated parts given to the [`path!`] macro.
+struct PathParts(Vec<PathPart>);
+
+enum PathPart {
+    Lit(LitStr),
+    Expr(Expr),
+}
+
+/// Checks that the given literal is a non-empty sequence of valid node names
+/// joined by the path separator.
+fn validate_relative(lit: &LitStr) -> syn::Result<()> {
+    let root = AbsPath::root().as_str();
+    let str = lit.value();
+    if str.is_empty() {
+        return Err(syn::Error::new_spanned(lit, "path component is empty"));
+    }
+    if str.starts_with(root) {
+        return Err(syn::Error::new_spanned(
+            lit,
+            "only the first part of the path can be absolute",
+        ));
+    }
+    <&AbsPath>::try_from(&*format!("{root}{str}"))
+        .map(|_| ())
+        .map_err(|err| diagnostics::invalid_abs_path(lit, root, err))
+}
+
+impl Parse for PathParts {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let exprs = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+        if exprs.is_empty() {
+            return Err(input.error("expected at least one path part"));
+        }
+        let parts = exprs
+            .into_iter()
+            .map(|expr| match expr {
+                Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) => {
+                    PathPart::Lit(lit)
+                },
+                expr => PathPart::Expr(expr),
+            })
+            .collect();
+        Ok(Self(parts))
+    }
+}
+
+/// Same as [`node!`], but evaluates to a `NodeNameBuf` created with
+/// `NodeNameBuf::from_static()`, so it can be used to initialize `const`s and
+/// `static`s.
+#[proc_macro]
+pub fn node_buf(input: TokenStream) -> TokenStream {
+    let node = proc_macro2::TokenStream::from(node(input));
+    quote! { ::abs_path::NodeNameBuf::from_static(#node) }.into()
+}
+
+/// Same as [`path!`], but evaluates to an `AbsPathBuf` created with
+/// `AbsPathBuf::from_static()`, so it can be used to initialize `const`s and
+/// `static`s.
+#[proc_macro]
+pub fn path_buf(input: TokenStream) -> TokenStream {
+    let path = proc_macro2::TokenStream::from(path(input));
+    quote! { ::abs_path::AbsPathBuf::from_static(#path) }.into()
+}
+
+/// Defines a struct that matches paths against a pattern and captures some
+/// of their components.
+///
+/// A `{name}` placeholder captures a single component as a `&NodeName`,
+/// while a `{*name}` placeholder can only appear at the end of the pattern
+/// and captures all the remaining components (possibly none) as a
+/// `&AbsPath`. Every other component must match exactly, and is validated at
+/// compile time.
+///
+/// The generated struct has one public field per placeholder and a
+/// `match_path()` constructor that returns `None` if the path doesn't match.
+///
+/// There's no relative path type, so a `{*name}` capture is the rest of the
+/// path as an `&AbsPath` of its own: it keeps its leading separator
+/// (`/lib.rs`, not `lib.rs`), and it's `AbsPath::root()` if there are no
+/// components left.
+///
+/// ```ignore
+/// path_pattern!(pub struct SrcFile = "/projects/{project}/src/{*rest}");
+///
+/// let file = SrcFile::match_path(path!("/projects/foo/src/lib.rs")).unwrap();
+/// assert_eq!(file.project, "foo");
+/// assert_eq!(file.rest, "/lib.rs");
+///
+/// let dir = SrcFile::match_path(path!("/projects/foo/src")).unwrap();
+/// assert!(dir.rest.is_root());
+/// ```
+#[proc_macro]
+pub fn path_pattern(input: TokenStream) -> TokenStream {
+    parse_macro_input!(input as path_pattern::PathPatternInput)
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a `resolve_paths()` method that resolves every field annotated
+/// with `#[resolve]` against a base `AbsPath`, e.g. the directory of the
+/// configuration file the struct was deserialized from.
+///
+/// The annotated fields must implement `AsRef<str>`, or be `Option`s of
+/// types that do. The method returns a `{Name}Paths` struct with an
+/// `AbsPathBuf` (or `Option<AbsPathBuf>`) for each of them, or a
+/// `ResolvePathsError` listing every field that couldn't be resolved.
+///
+/// Relative values are joined to the base and normalized, while absolute
+/// values are only normalized. The attribute accepts the following options:
+///
+/// - `allow_escape`: allow relative values to navigate above the base via
+///   `..` components;
+/// - `expand_tilde`: replace a leading `~` with the home directory;
+/// - `must_exist`: fail if nothing exists at the resolved path.
+///
+/// ```ignore
+/// #[derive(Deserialize, ResolvePaths)]
+/// struct Config {
+///     #[resolve(must_exist)]
+///     root: String,
+///     #[resolve(expand_tilde, allow_escape)]
+///     cache_dir: Option<String>,
+/// }
+///
+/// let paths = config.resolve_paths(config_dir)?;
+/// ```
+#[proc_macro_derive(ResolvePaths, attributes(resolve))]
+pub fn resolve_paths(input: TokenStream) -> TokenStream {
+    resolve_paths::expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
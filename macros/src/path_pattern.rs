@@ -0,0 +1,213 @@
+use abs_path_core::{AbsPath, NodeName};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{Attribute, Ident, LitStr, Token, Visibility};
+
+/// The input of the `path_pattern!` macro, i.e. a struct declaration whose
+/// "body" is the pattern literal.
+pub(crate) struct PathPatternInput {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    pattern: LitStr,
+}
+
+enum Segment {
+    /// A component that must be equal to the given name.
+    Fixed(String),
+
+    /// A `{name}` placeholder capturing a single component.
+    Capture(Ident),
+
+    /// A `{*name}` placeholder capturing all the remaining components.
+    Rest(Ident),
+}
+
+impl PathPatternInput {
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        let segments = self.parse_pattern()?;
+
+        let Self { attrs, vis, name, pattern } = &self;
+
+        // Use mixed-site hygiene so that captures can't shadow these.
+        let path = Ident::new("path", Span::mixed_site());
+        let components = Ident::new("components", Span::mixed_site());
+
+        let mut fields = Vec::new();
+        let mut field_names = Vec::new();
+        let mut steps = Vec::new();
+        let mut has_rest = false;
+
+        for segment in &segments {
+            match segment {
+                Segment::Fixed(fixed) => steps.push(quote! {
+                    if #components.next()?.as_str() != #fixed {
+                        return ::core::option::Option::None;
+                    }
+                }),
+                Segment::Capture(ident) => {
+                    let doc = format!("The `{}` capture.", ident.unraw());
+                    fields.push(quote! {
+                        #[doc = #doc]
+                        pub #ident: &'path ::abs_path::NodeName
+                    });
+                    field_names.push(ident);
+                    steps.push(quote! { let #ident = #components.next()?; });
+                },
+                Segment::Rest(ident) => {
+                    let doc = format!(
+                        "The `{}` capture, i.e. the remaining components as \
+                         an absolute path.",
+                        ident.unraw()
+                    );
+                    fields.push(quote! {
+                        #[doc = #doc]
+                        pub #ident: &'path ::abs_path::AbsPath
+                    });
+                    field_names.push(ident);
+                    steps.push(quote! { let #ident = #components.as_path(); });
+                    has_rest = true;
+                },
+            }
+        }
+
+        if !has_rest {
+            steps.push(quote! {
+                if #components.next().is_some() {
+                    return ::core::option::Option::None;
+                }
+            });
+        }
+
+        let doc = format!(
+            "Matches paths against the `{}` pattern.",
+            pattern.value()
+        );
+
+        let (struct_def, self_ty, path_lifetime) = if fields.is_empty() {
+            (quote! { #vis struct #name; }, quote! { #name }, quote! {})
+        } else {
+            (
+                quote! { #vis struct #name<'path> { #(#fields),* } },
+                quote! { #name<'path> },
+                quote! { 'path },
+            )
+        };
+
+        let impl_generics = if fields.is_empty() {
+            quote! {}
+        } else {
+            quote! { <'path> }
+        };
+
+        Ok(quote! {
+            #(#attrs)*
+            #struct_def
+
+            impl #impl_generics #self_ty {
+                /// The pattern this type was generated from.
+                #vis const PATTERN: &'static str = #pattern;
+
+                #[doc = #doc]
+                ///
+                /// Returns the captured components if the path matches, or
+                /// `None` otherwise.
+                #[inline]
+                #vis fn match_path(
+                    #path: &#path_lifetime ::abs_path::AbsPath,
+                ) -> ::core::option::Option<Self> {
+                    let mut #components = #path.components();
+                    #(#steps)*
+                    ::core::option::Option::Some(Self { #(#field_names),* })
+                }
+            }
+        })
+    }
+
+    fn parse_pattern(&self) -> syn::Result<Vec<Segment>> {
+        let error = |msg: &str| syn::Error::new_spanned(&self.pattern, msg);
+
+        let str = self.pattern.value();
+        let separator = AbsPath::root().as_str();
+
+        let Some(rest) = str.strip_prefix(separator) else {
+            return Err(error("pattern must start with a path separator"));
+        };
+
+        let mut segments = Vec::new();
+
+        if rest.is_empty() {
+            return Ok(segments);
+        }
+
+        let mut names = Vec::<String>::new();
+
+        for component in rest.split(separator) {
+            if matches!(segments.last(), Some(Segment::Rest(_))) {
+                return Err(error(
+                    "a `{*rest}` placeholder can only appear at the end of \
+                     the pattern",
+                ));
+            }
+
+            let segment = match component
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+            {
+                Some(placeholder) => {
+                    let (is_rest, name) = match placeholder.strip_prefix('*') {
+                        Some(name) => (true, name),
+                        None => (false, placeholder),
+                    };
+                    let Ok(mut ident) = syn::parse_str::<Ident>(name) else {
+                        return Err(error(
+                            "placeholder names must be identifiers",
+                        ));
+                    };
+                    if names.iter().any(|n| n == name) {
+                        return Err(error("placeholder names must be unique"));
+                    }
+                    names.push(name.to_owned());
+                    ident.set_span(self.pattern.span());
+                    if is_rest {
+                        Segment::Rest(ident)
+                    } else {
+                        Segment::Capture(ident)
+                    }
+                },
+                None => {
+                    if component.contains(['{', '}']) {
+                        return Err(error(
+                            "placeholders must span a whole component",
+                        ));
+                    }
+                    if let Err(err) = NodeName::from_str(component) {
+                        return Err(error(&err.to_string()));
+                    }
+                    Segment::Fixed(component.to_owned())
+                },
+            };
+
+            segments.push(segment);
+        }
+
+        Ok(segments)
+    }
+}
+
+impl Parse for PathPatternInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let pattern = input.parse()?;
+        if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+        }
+        Ok(Self { attrs, vis, name, pattern })
+    }
+}
@@ -0,0 +1,211 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{
+    Data,
+    DeriveInput,
+    Fields,
+    GenericArgument,
+    Ident,
+    LitStr,
+    PathArguments,
+    Type,
+    Visibility,
+};
+
+/// A field annotated with `#[resolve]`.
+struct ResolvedField {
+    ident: Ident,
+    vis: Visibility,
+    is_option: bool,
+    allow_escape: bool,
+    expand_tilde: bool,
+    must_exist: bool,
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`ResolvePaths` can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`ResolvePaths` can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut resolved = Vec::new();
+
+    for field in &fields.named {
+        for attr in &field.attrs {
+            if !attr.path().is_ident("resolve") {
+                continue;
+            }
+            let Some(ident) = field.ident.clone() else { continue };
+            let mut resolved_field = ResolvedField {
+                ident,
+                vis: field.vis.clone(),
+                is_option: is_option(&field.ty),
+                allow_escape: false,
+                expand_tilde: false,
+                must_exist: false,
+            };
+            if !matches!(attr.meta, syn::Meta::Path(_)) {
+                attr.parse_nested_meta(|meta| {
+                    let flag = if meta.path.is_ident("allow_escape") {
+                        &mut resolved_field.allow_escape
+                    } else if meta.path.is_ident("expand_tilde") {
+                        &mut resolved_field.expand_tilde
+                    } else if meta.path.is_ident("must_exist") {
+                        &mut resolved_field.must_exist
+                    } else {
+                        return Err(meta.error(
+                            "expected one of `allow_escape`, `expand_tilde` \
+                             or `must_exist`",
+                        ));
+                    };
+                    *flag = true;
+                    Ok(())
+                })?;
+            }
+            resolved.push(resolved_field);
+            break;
+        }
+    }
+
+    if resolved.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "no fields are annotated with `#[resolve]`",
+        ));
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let paths_name = format_ident!("{}Paths", name);
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    // Use mixed-site hygiene so that fields can't shadow these.
+    let base = Ident::new("base", Span::mixed_site());
+    let resolver = Ident::new("resolver", Span::mixed_site());
+
+    let paths_fields = resolved.iter().map(|field| {
+        let ResolvedField { ident, vis, .. } = field;
+        let doc = format!("The resolved `{}` field.", ident.unraw());
+        if field.is_option {
+            quote! {
+                #[doc = #doc]
+                #vis #ident: ::core::option::Option<::abs_path::AbsPathBuf>
+            }
+        } else {
+            quote! {
+                #[doc = #doc]
+                #vis #ident: ::abs_path::AbsPathBuf
+            }
+        }
+    });
+
+    let statements = resolved.iter().map(|field| {
+        let ResolvedField {
+            ident,
+            allow_escape,
+            expand_tilde,
+            must_exist,
+            ..
+        } = field;
+        let field_name = LitStr::new(&ident.unraw().to_string(), ident.span());
+        let resolve = |value: TokenStream| {
+            quote! {
+                #resolver.resolve(
+                    #field_name,
+                    ::core::convert::AsRef::<str>::as_ref(#value),
+                    ::abs_path::__private::ResolveOptions {
+                        allow_escape: #allow_escape,
+                        expand_tilde: #expand_tilde,
+                        must_exist: #must_exist,
+                    },
+                )
+            }
+        };
+        if field.is_option {
+            let value = Ident::new("value", Span::mixed_site());
+            let resolve = resolve(quote! { #value });
+            quote! {
+                let #ident = match &self.#ident {
+                    ::core::option::Option::Some(#value) => {
+                        #resolve.map(::core::option::Option::Some)
+                    },
+                    ::core::option::Option::None => {
+                        ::core::option::Option::Some(
+                            ::core::option::Option::None,
+                        )
+                    },
+                };
+            }
+        } else {
+            let resolve = resolve(quote! { &self.#ident });
+            quote! { let #ident = #resolve; }
+        }
+    });
+
+    let idents = resolved.iter().map(|field| &field.ident).collect::<Vec<_>>();
+
+    let struct_doc = format!(
+        "The paths of a [`{name}`], resolved by [`{name}::resolve_paths()`]."
+    );
+
+    Ok(quote! {
+        #[doc = #struct_doc]
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        #vis struct #paths_name {
+            #(#paths_fields,)*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Resolves every field annotated with `#[resolve]` against the
+            /// given base path.
+            ///
+            /// Every field is resolved even if some fail, and the returned
+            /// error contains all the failures.
+            #vis fn resolve_paths(
+                &self,
+                #base: &::abs_path::AbsPath,
+            ) -> ::core::result::Result<
+                #paths_name,
+                ::abs_path::ResolvePathsError,
+            > {
+                let mut #resolver =
+                    ::abs_path::__private::PathResolver::new(#base);
+                #(#statements)*
+                match (#(#idents,)*) {
+                    (#(::core::option::Option::Some(#idents),)*) => {
+                        ::core::result::Result::Ok(#paths_name {
+                            #(#idents,)*
+                        })
+                    },
+                    _ => ::core::result::Result::Err(#resolver.into_error()),
+                }
+            }
+        }
+    })
+}
+
+/// Returns whether the type is syntactically an `Option<T>`.
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else { return false };
+    let Some(segment) = ty.path.segments.last() else { return false };
+    segment.ident == "Option"
+        && matches!(
+            &segment.arguments,
+            PathArguments::AngleBracketed(args)
+                if matches!(
+                    args.args.first(),
+                    Some(GenericArgument::Type(_))
+                ) && args.args.len() == 1
+        )
+}
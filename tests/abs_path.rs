@@ -1,6 +1,28 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
 
-use abs_path::{AbsPath, AbsPathBuf, NodeName, NormalizeError, path};
+use abs_path::{
+    AbsPath,
+    AbsPathBuf,
+    AbsPathFromPathError,
+    CaseInsensitive,
+    GenericAbsPathBuf,
+    IndexedAbsPath,
+    IndexedAbsPathBuf,
+    InvalidAbsPathError,
+    InvalidNodeNameError,
+    NfcEq,
+    NodeName,
+    NormalizeError,
+    StackAbsPathBuf,
+    format_path,
+    node,
+    path,
+    path_buf,
+    path_pattern,
+};
 
 #[test]
 fn components_empty() {
@@ -61,6 +83,91 @@ fn from_single_separator() {
     assert_eq!(<&AbsPath>::try_from("/").unwrap(), AbsPath::root());
 }
 
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn from_str_not_absolute() {
+    assert_eq!(AbsPath::from_str(""), Err(InvalidAbsPathError::NotAbsolute));
+    assert_eq!(
+        AbsPath::from_str("foo/bar"),
+        Err(InvalidAbsPathError::NotAbsolute)
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn from_str_empty_component() {
+    assert_eq!(
+        AbsPath::from_str("/foo//bar"),
+        Err(InvalidAbsPathError::EmptyComponent {
+            byte_offset: 5,
+            component_idx: 1
+        })
+    );
+    assert_eq!(
+        AbsPath::from_str("/foo/"),
+        Err(InvalidAbsPathError::EmptyComponent {
+            byte_offset: 5,
+            component_idx: 1
+        })
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn from_str_dot_components() {
+    assert_eq!(
+        AbsPath::from_str("/foo/./bar"),
+        Err(InvalidAbsPathError::SingleDotComponent {
+            byte_offset: 5,
+            component_idx: 1
+        })
+    );
+    assert_eq!(
+        AbsPath::from_str("/foo/bar/.."),
+        Err(InvalidAbsPathError::DoubleDotComponent {
+            byte_offset: 9,
+            component_idx: 2
+        })
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn from_str_invalid_character() {
+    const ERR: InvalidAbsPathError = match AbsPath::from_str("/a/b/c/d/ef\0g")
+    {
+        Ok(_) => panic!("path is valid"),
+        Err(err) => err,
+    };
+    assert_eq!(
+        ERR,
+        InvalidAbsPathError::InvalidCharacter {
+            byte_offset: 11,
+            component_idx: 4,
+            ch: '\0'
+        }
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn try_from_path_errors() {
+    fn try_from(path: &str) -> Result<&AbsPath, AbsPathFromPathError> {
+        <&AbsPath>::try_from(Path::new(path))
+    }
+    assert_eq!(try_from("/foo").unwrap(), path!("/foo"));
+    assert_eq!(try_from("foo"), Err(AbsPathFromPathError::NotAbsolute));
+    assert_eq!(
+        try_from("/foo/."),
+        Err(AbsPathFromPathError::Invalid(
+            InvalidAbsPathError::SingleDotComponent {
+                byte_offset: 5,
+                component_idx: 1
+            }
+        ))
+    );
+}
+
 #[test]
 #[cfg_attr(target_os = "windows", ignore)]
 fn from_iter_1() {
@@ -149,6 +256,16 @@ fn normalize_11() {
     assert_eq!(AbsPath::normalize(p), Ok(Cow::Borrowed(path!("/bar/baz"))));
 }
 
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn normalize_invalid_character() {
+    let p = "/foo/ba\0r";
+    assert_eq!(
+        AbsPath::normalize(p),
+        Err(NormalizeError::InvalidCharacter { byte_offset: 7, ch: '\0' })
+    );
+}
+
 #[test]
 #[cfg_attr(target_os = "windows", ignore)]
 fn starts_with() {
@@ -158,6 +275,10 @@ fn starts_with() {
     assert!(p.starts_with(path!("/foo/bar")));
     assert!(!p.starts_with(path!("/foo/bar.rs")));
     assert!(!p.starts_with(path!("/foo/bar/baz")));
+
+    let base = path!("/foo").to_owned();
+    assert!(p.starts_with(&base));
+    assert!(p.starts_with(base));
 }
 
 #[test]
@@ -166,3 +287,364 @@ fn strip_prefix_root() {
     let p = path!("/foo/bar");
     assert_eq!(p.strip_prefix(AbsPath::root()).unwrap(), "/foo/bar");
 }
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn case_insensitive_eq_and_hash() {
+    use std::collections::HashSet;
+
+    let lhs = CaseInsensitive(path!("/Src/Main.rs"));
+    let rhs = CaseInsensitive(path!("/src/MAIN.RS"));
+    assert_eq!(lhs, rhs);
+
+    let set = [lhs].into_iter().collect::<HashSet<_>>();
+    assert!(set.contains(&rhs));
+
+    // Simple case folding maps `ς` and `Σ` to `σ`, but doesn't expand `ß`.
+    assert_eq!(
+        CaseInsensitive(path!("/ΟΔΟΣ")),
+        CaseInsensitive(path!("/οδος"))
+    );
+    assert_ne!(CaseInsensitive(path!("/ß")), CaseInsensitive(path!("/SS")));
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn case_insensitive_ord() {
+    let lhs = CaseInsensitive(path!("/B"));
+    let rhs = CaseInsensitive(path!("/a"));
+    assert!(lhs > rhs);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn case_insensitive_strip_prefix() {
+    let p = CaseInsensitive(path!("/Foo/Bar/baz.txt"));
+    assert!(p.starts_with(path!("/foo/bar")));
+    assert!(!p.starts_with(path!("/foo/ba")));
+    assert_eq!(p.strip_prefix(path!("/FOO")).unwrap(), "/Bar/baz.txt");
+    assert_eq!(p.strip_prefix(path!("/foo/bar/BAZ.TXT")).unwrap(), "/");
+    assert_eq!(p.strip_prefix(AbsPath::root()).unwrap(), "/Foo/Bar/baz.txt");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn to_nfc() {
+    let nfd = AbsPath::from_str("/cafe\u{301}/na\u{308}ive").unwrap();
+    let nfc = AbsPath::from_str("/caf\u{e9}/n\u{e4}ive").unwrap();
+    assert_eq!(nfd.to_nfc().as_ref(), nfc);
+    assert_eq!(nfc.to_nfd().as_ref(), nfd);
+    assert!(matches!(nfc.to_nfc(), Cow::Borrowed(_)));
+    assert!(matches!(nfd.to_nfd(), Cow::Borrowed(_)));
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn nfc_eq() {
+    use std::collections::HashSet;
+
+    let nfd = NfcEq(AbsPath::from_str("/cafe\u{301}").unwrap());
+    let nfc = NfcEq(AbsPath::from_str("/caf\u{e9}").unwrap());
+    assert_eq!(nfd, nfc);
+
+    let set = [nfd].into_iter().collect::<HashSet<_>>();
+    assert!(set.contains(&nfc));
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_macro_interpolated() {
+    const APP: &NodeName = node!("my-app");
+    const CONFIG: &AbsPath = path!("/etc", APP, "conf.d/config.toml");
+    assert_eq!(CONFIG, "/etc/my-app/conf.d/config.toml");
+    assert_eq!(path!("/", APP), "/my-app");
+    assert_eq!(path!(APP, node!("bin")), "/my-app/bin");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn format_path() {
+    let id = 42;
+    let path = format_path!("/users/{id:04}/avatars/{name}.png", name = "me");
+    assert_eq!(path.unwrap(), "/users/0042/avatars/me.png");
+    assert_eq!(format_path!("/").unwrap(), "/");
+    assert_eq!(format_path!("/{{{id}}}").unwrap(), "/{42}");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn format_path_invalid_value() {
+    let err = format_path!("/users/{id}/{name}.png", id = 1, name = "../x")
+        .unwrap_err();
+    assert_eq!(err.placeholder(), "name");
+    assert_eq!(
+        err.node_name_error(),
+        InvalidNodeNameError::ContainsInvalidCharacter('/')
+    );
+
+    let err = format_path!("/users/{id}", id = "").unwrap_err();
+    assert_eq!(err.placeholder(), "id");
+    assert_eq!(err.node_name_error(), InvalidNodeNameError::Empty);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_pattern() {
+    path_pattern!(struct SrcFile = "/projects/{project}/src/{*rest}");
+
+    let file = SrcFile::match_path(path!("/projects/foo/src/a/b.rs")).unwrap();
+    assert_eq!(file.project, "foo");
+    assert_eq!(file.rest, "/a/b.rs");
+
+    let file = SrcFile::match_path(path!("/projects/foo/src")).unwrap();
+    assert_eq!(file.rest, AbsPath::root());
+
+    assert!(SrcFile::match_path(path!("/projects/foo/tests/a.rs")).is_none());
+    assert!(SrcFile::match_path(path!("/projects")).is_none());
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_pattern_without_rest() {
+    path_pattern!(struct Manifest = "/projects/{project}/Cargo.toml");
+
+    let manifest =
+        Manifest::match_path(path!("/projects/foo/Cargo.toml")).unwrap();
+    assert_eq!(manifest.project, "foo");
+    assert!(
+        Manifest::match_path(path!("/projects/foo/Cargo.toml/x")).is_none()
+    );
+
+    path_pattern!(struct Etc = "/etc");
+    assert!(Etc::match_path(path!("/etc")).is_some());
+    assert!(Etc::match_path(path!("/etc/hosts")).is_none());
+}
+
+/// Checks that the items generated by `path_pattern!` are documented.
+#[deny(missing_docs)]
+pub mod documented_path_pattern {
+    abs_path::path_pattern!(
+        /// A source file.
+        pub struct SrcFile = "/projects/{project}/src/{*rest}"
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_buf_macro() {
+    static SHORT: AbsPathBuf = path_buf!("/etc");
+    static LONG: AbsPathBuf =
+        path_buf!("/usr/local/share/applications/my-app");
+    assert_eq!(SHORT, "/etc");
+    assert_eq!(LONG, "/usr/local/share/applications/my-app");
+    assert_eq!(SHORT.clone().join(node!("hosts")), "/etc/hosts");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn const_path_ops() {
+    const PATH: &AbsPath = path!("/foo/bar/baz");
+    const { assert!(PATH.starts_with_const(path!("/foo/bar"))) };
+    const { assert!(!PATH.starts_with_const(path!("/foo/ba"))) };
+    const { assert!(PATH.eq_const(path!("/foo/bar/baz"))) };
+    const FIRST: &NodeName = match PATH.components().next_const() {
+        Some(first) => first,
+        None => panic!(),
+    };
+    const COUNT: usize = {
+        let mut components = PATH.components();
+        let mut count = 0;
+        while components.next_const().is_some() {
+            count += 1;
+        }
+        count
+    };
+    assert!(FIRST.eq_const(node!("foo")));
+    assert_eq!(COUNT, 3);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn stack_abs_path_buf_const() {
+    const BASE: StackAbsPathBuf<32> =
+        StackAbsPathBuf::new_const(path!("/srv/app"));
+    const LOGS: StackAbsPathBuf<32> = BASE.join_const(node!("logs"));
+    const CONFIG: StackAbsPathBuf<32> =
+        BASE.concat_const(path!("/etc/config.toml"));
+    const PARENT: StackAbsPathBuf<32> = {
+        let mut path = CONFIG;
+        path.pop();
+        path
+    };
+    const REQUIRED_LEN: usize =
+        match BASE.join(node!("a-much-longer-file-name.txt")) {
+            Ok(_) => 0,
+            Err(err) => err.required_len(),
+        };
+    assert_eq!(LOGS, "/srv/app/logs");
+    assert_eq!(CONFIG, "/srv/app/etc/config.toml");
+    assert_eq!(PARENT, "/srv/app/etc");
+    assert_eq!(REQUIRED_LEN, 36);
+    assert_eq!(StackAbsPathBuf::<1>::root(), AbsPath::root());
+    assert_eq!(AbsPathBuf::from(LOGS), "/srv/app/logs");
+}
+
+#[test]
+#[should_panic(expected = "capacity")]
+fn stack_abs_path_buf_const_overflow() {
+    let _ = StackAbsPathBuf::<4>::root().join_const(node!("foobar"));
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn stack_abs_path_buf() {
+    let mut path = StackAbsPathBuf::<16>::root();
+    path.push(node!("foo")).unwrap().push(node!("bar")).unwrap();
+    assert_eq!(path, "/foo/bar");
+    assert!(path.pop());
+    assert_eq!(path, "/foo");
+    let path = path.concat(path!("/bar/baz")).unwrap();
+    assert_eq!(path, "/foo/bar/baz");
+    assert_eq!(
+        StackAbsPathBuf::<16>::root().concat(path!("/a/b")).unwrap(),
+        "/a/b"
+    );
+    assert_eq!(AbsPathBuf::from(path), "/foo/bar/baz");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn stack_abs_path_buf_capacity() {
+    let mut path = StackAbsPathBuf::<8>::new(path!("/foo")).unwrap();
+
+    let err = path.push(node!("barbaz")).unwrap_err();
+    assert_eq!(err.capacity(), 8);
+    assert_eq!(err.required_len(), 11);
+    assert_eq!(path, "/foo");
+
+    let err = path.concat_mut(path!("/a/b/c")).unwrap_err();
+    assert_eq!(err.required_len(), 10);
+    assert_eq!(path, "/foo");
+
+    let mut root = StackAbsPathBuf::<4>::root();
+    assert!(root.concat_mut(path!("/a/b/c")).is_err());
+    assert!(root.is_root());
+
+    assert!(StackAbsPathBuf::<3>::new(path!("/foo")).is_err());
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn abs_path_buf_storage() {
+    let mut path = GenericAbsPathBuf::<String>::from(path!("/foo"));
+    path.push(node!("bar"));
+    assert_eq!(path, "/foo/bar");
+    assert_eq!(path, path_buf!("/foo/bar"));
+    assert_eq!(path.clone().into_storage(), "/foo/bar");
+    assert_eq!(std::path::PathBuf::from(path), Path::new("/foo/bar"));
+
+    let mut path = GenericAbsPathBuf::<Arc<str>>::from(path!("/foo/bar"));
+    let clone = path.clone();
+    assert!(path.pop());
+    assert_eq!(path, "/foo");
+    assert_eq!(clone, "/foo/bar");
+    assert_eq!(path.concat(path!("/baz")), "/foo/baz");
+
+    let set = [GenericAbsPathBuf::<Box<str>>::from(path!("/foo"))]
+        .into_iter()
+        .collect::<HashSet<_>>();
+    assert!(set.contains(path!("/foo")));
+
+    assert_eq!(
+        GenericAbsPathBuf::<String>::from_storage("foo".to_owned()),
+        Err(InvalidAbsPathError::NotAbsolute)
+    );
+
+    let mut root = GenericAbsPathBuf::<Arc<str>>::root_in();
+    assert!(root.is_root());
+    root.push(node!("foo"));
+    assert_eq!(root, "/foo");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn abs_path_buf_inference() {
+    // None of these should need a type annotation for the storage.
+    let path = AbsPathBuf::try_from("/foo").unwrap();
+    assert_eq!(path, "/foo");
+    let path = AbsPathBuf::from(path!("/a"));
+    assert_eq!(path, "/a");
+    let path: AbsPathBuf = "/foo/bar".parse().unwrap();
+    assert_eq!(std::path::PathBuf::from(path), Path::new("/foo/bar"));
+    let path = AbsPathBuf::try_from(Path::new("/foo")).unwrap();
+    assert_eq!(std::ffi::OsString::from(path), "/foo");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn indexed_abs_path() {
+    let path = IndexedAbsPath::new(path!("/foo/bar/baz.rs"));
+    assert_eq!(path.depth(), 3);
+    assert_eq!(path.component(0).unwrap(), "foo");
+    assert_eq!(path.component(2).unwrap(), "baz.rs");
+    assert_eq!(path.component(3), None);
+    assert_eq!(path.ancestor(0).unwrap(), AbsPath::root());
+    assert_eq!(path.ancestor(2).unwrap(), "/foo/bar");
+    assert_eq!(path.ancestor(3).unwrap(), "/foo/bar/baz.rs");
+    assert_eq!(path.ancestor(4), None);
+    assert_eq!(path.as_path(), path!("/foo/bar/baz.rs"));
+
+    let root = IndexedAbsPath::new(AbsPath::root());
+    assert_eq!(root.depth(), 0);
+    assert_eq!(root.component(0), None);
+    assert_eq!(root.ancestor(0).unwrap(), AbsPath::root());
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn indexed_abs_path_buf() {
+    let mut path = IndexedAbsPathBuf::new(AbsPathBuf::root());
+    path.push(node!("foo")).push(node!("bar"));
+    assert_eq!(path.depth(), 2);
+    assert_eq!(path.component(1).unwrap(), "bar");
+    assert_eq!(path.ancestor(1).unwrap(), "/foo");
+    assert!(path.pop());
+    assert_eq!(path.depth(), 1);
+    assert_eq!(path.component(1), None);
+    path.push(node!("baz"));
+    assert_eq!(path, IndexedAbsPath::new(path!("/foo/baz")).to_indexed_buf());
+    assert!(path.pop() && path.pop() && !path.pop());
+}
+
+#[test]
+fn from_str_const_and_runtime_agree() {
+    let inputs = [
+        "",
+        "foo",
+        "/",
+        "//",
+        "/foo",
+        "/foo/",
+        "/foo//bar",
+        "/./foo",
+        "/foo/..",
+        "/foo/..bar/.baz",
+        "/foo\0/bar",
+        "/foo/b\0ar/..",
+        "/foo/../b\0ar",
+        "/f\u{e9}e/\u{1f600}/x\0y",
+        "/a:b/c*d/e|f",
+    ];
+    for input in inputs {
+        assert_eq!(
+            AbsPath::from_str(input),
+            <&AbsPath>::try_from(input),
+            "{input:?}"
+        );
+        assert_eq!(
+            NodeName::from_str(input),
+            <&NodeName>::try_from(input),
+            "{input:?}"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use abs_path::clap_parsers::AbsPathBufValueParser;
+use abs_path::{AbsPathBuf, NodeNameBuf};
+use clap::{Arg, Command};
+
+fn parse<T: Clone + Send + Sync + 'static>(
+    parser: impl Into<clap::builder::ValueParser>,
+    value: &str,
+) -> Result<T, String> {
+    Command::new("prog")
+        .arg(Arg::new("value").value_parser(parser))
+        .try_get_matches_from(["prog", value])
+        .map(|mut matches| matches.remove_one::<T>("value").unwrap())
+        .map_err(|err| err.to_string())
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn clap_abs_path_buf() {
+    let path = parse::<AbsPathBuf>(
+        clap::value_parser!(AbsPathBuf),
+        "/foo//bar/./baz/..",
+    )
+    .unwrap();
+    assert_eq!(path, "/foo/bar");
+
+    let current_dir = std::env::current_dir().unwrap();
+    let path =
+        parse::<AbsPathBuf>(clap::value_parser!(AbsPathBuf), "src").unwrap();
+    assert_eq!(path, current_dir.join("src").to_str().unwrap());
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn clap_abs_path_buf_errors() {
+    let err =
+        parse::<AbsPathBuf>(clap::value_parser!(AbsPathBuf), "/foo/b\0r/baz")
+            .unwrap_err();
+    assert!(err.contains("invalid value '/foo/b\0r/baz'"), "{err}");
+    assert!(err.contains("component 'b\0r'"), "{err}");
+
+    let err =
+        parse::<AbsPathBuf>(clap::value_parser!(AbsPathBuf), "/foo/../..")
+            .unwrap_err();
+    assert!(err.contains("component '..' at byte 8"), "{err}");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn clap_abs_path_buf_must_exist() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let manifest = format!("{manifest_dir}/Cargo.toml");
+
+    parse::<AbsPathBuf>(
+        AbsPathBufValueParser::new().must_be_dir(),
+        manifest_dir,
+    )
+    .unwrap();
+    parse::<AbsPathBuf>(
+        AbsPathBufValueParser::new().must_be_file(),
+        &manifest,
+    )
+    .unwrap();
+
+    let err = parse::<AbsPathBuf>(
+        AbsPathBufValueParser::new().must_be_dir(),
+        &manifest,
+    )
+    .unwrap_err();
+    assert!(err.contains("is not a directory"), "{err}");
+
+    let err = parse::<AbsPathBuf>(
+        AbsPathBufValueParser::new().must_exist(),
+        "/this/does/not/exist",
+    )
+    .unwrap_err();
+    assert!(err.contains("'/this/does/not/exist' does not exist"), "{err}");
+
+    // Errors other than a missing path are reported as they are.
+    let err = parse::<AbsPathBuf>(
+        AbsPathBufValueParser::new().must_exist(),
+        &format!("{manifest}/child"),
+    )
+    .unwrap_err();
+    assert!(err.contains("couldn't access"), "{err}");
+    assert!(!err.contains("does not exist"), "{err}");
+}
+
+#[test]
+fn clap_node_name_buf() {
+    let name =
+        parse::<NodeNameBuf>(clap::value_parser!(NodeNameBuf), "foo.txt")
+            .unwrap();
+    assert_eq!(name.as_str(), "foo.txt");
+
+    let err = parse::<NodeNameBuf>(clap::value_parser!(NodeNameBuf), "..")
+        .unwrap_err();
+    assert!(err.contains("invalid value '..'"), "{err}");
+}
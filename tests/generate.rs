@@ -0,0 +1,88 @@
+use abs_path::{
+    AbsPath,
+    AbsPathBuf,
+    Alphabet,
+    GenerateOptions,
+    NodeName,
+    NodeNameBuf,
+};
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    #[cfg_attr(target_os = "windows", ignore)]
+    fn proptest_abs_path_buf(path in any::<AbsPathBuf>()) {
+        prop_assert!(<&AbsPath>::try_from(path.as_str()).is_ok());
+        prop_assert!(path.components().count() <= 8);
+    }
+
+    #[test]
+    fn proptest_node_name_buf(
+        name in any_with::<NodeNameBuf>(
+            GenerateOptions::default()
+                .alphabet(Alphabet::PlatformInvalid)
+                .max_name_len(4),
+        ),
+    ) {
+        prop_assert!(<&NodeName>::try_from(name.as_str()).is_ok());
+        prop_assert!(name.as_str().chars().count() <= 4);
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "windows", ignore)]
+    fn proptest_almost_valid_path(
+        str in abs_path::proptest_gen::almost_valid_path(
+            GenerateOptions::default().alphabet(Alphabet::Unicode),
+        ),
+    ) {
+        // Normalizing can only fail by escaping the root.
+        if let Ok(path) = AbsPath::normalize(&str) {
+            prop_assert!(<&AbsPath>::try_from(path.as_str()).is_ok());
+        }
+    }
+}
+
+#[test]
+fn arbitrary_generators() {
+    let bytes =
+        (0..4096).map(|idx| (idx * 31 % 251) as u8).collect::<Vec<_>>();
+    let mut u = Unstructured::new(&bytes);
+
+    let options =
+        GenerateOptions::default().alphabet(Alphabet::Unicode).max_depth(3);
+
+    while !u.is_empty() {
+        let name = NodeNameBuf::arbitrary(&mut u).unwrap();
+        assert!(<&NodeName>::try_from(name.as_str()).is_ok());
+
+        let path: AbsPathBuf =
+            abs_path::arbitrary_gen::abs_path_buf(&mut u, &options).unwrap();
+        assert!(<&AbsPath>::try_from(path.as_str()).is_ok());
+        assert!(path.components().count() <= 3);
+
+        let str = abs_path::arbitrary_gen::almost_valid_path(&mut u, &options)
+            .unwrap();
+        assert!(str.starts_with(std::path::MAIN_SEPARATOR));
+    }
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn proptest_shrinks_by_removing_components() {
+    use proptest::test_runner::{TestError, TestRunner};
+
+    let mut runner = TestRunner::deterministic();
+
+    let result = runner.run(&any::<AbsPathBuf>(), |path| {
+        prop_assert!(path.components().count() < 2);
+        Ok(())
+    });
+
+    let Err(TestError::Fail(_, minimal)) = result else {
+        panic!("expected the test to fail");
+    };
+
+    assert_eq!(minimal.components().count(), 2);
+    assert!(minimal.components().all(|name| name.len() == 1));
+}
@@ -0,0 +1,335 @@
+use abs_path::{
+    CaseInsensitive,
+    InvalidNodeNameError,
+    NodeName,
+    NodeNameBuf,
+    PortabilityError,
+    PortabilityProfile,
+    SanitizeOptions,
+    StackNodeNameBuf,
+    node,
+    node_buf,
+};
+
+#[test]
+fn validate_for_posix() {
+    let name = node!("a:b.");
+    assert_eq!(name.validate_for(PortabilityProfile::Posix), Ok(()));
+}
+
+#[test]
+fn validate_for_macos_colon() {
+    let name = node!("a:b");
+    assert_eq!(
+        name.validate_for(PortabilityProfile::MacOS),
+        Err(PortabilityError::ContainsInvalidCharacter(':'))
+    );
+}
+
+#[test]
+fn validate_for_windows_reserved_names() {
+    for name in ["aux", "AUX.txt", "con.tar.gz", "Com1", "lpt9.log", "nul .c"]
+    {
+        let name = NodeName::from_str(name).unwrap();
+        assert_eq!(
+            name.validate_for(PortabilityProfile::Windows),
+            Err(PortabilityError::ReservedName),
+            "{name}"
+        );
+    }
+
+    for name in ["auxiliary", "com10", "lpt", "xcon.txt", "COM0.txt", "lpt0"] {
+        let name = NodeName::from_str(name).unwrap();
+        assert_eq!(
+            name.validate_for(PortabilityProfile::Windows),
+            Ok(()),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn validate_for_windows_trailing_dot_or_space() {
+    assert_eq!(
+        node!("foo.").validate_for(PortabilityProfile::Windows),
+        Err(PortabilityError::TrailingDot)
+    );
+    assert_eq!(
+        node!("foo ").validate_for(PortabilityProfile::Windows),
+        Err(PortabilityError::TrailingSpace)
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn validate_for_windows_control_character() {
+    let name = NodeName::from_str("foo\u{1}bar").unwrap();
+    assert_eq!(
+        name.validate_for(PortabilityProfile::Windows),
+        Err(PortabilityError::ContainsControlCharacter('\u{1}'))
+    );
+}
+
+#[test]
+fn validate_for_too_long() {
+    let long = "é".repeat(200);
+    let name = NodeName::from_str(&long).unwrap();
+
+    // 200 UTF-16 code units, but 400 bytes.
+    assert_eq!(name.validate_for(PortabilityProfile::Windows), Ok(()));
+    assert_eq!(
+        name.validate_for(PortabilityProfile::Portable),
+        Err(PortabilityError::TooLong { len: 400, max_len: 255 })
+    );
+}
+
+#[test]
+fn validate_for_into_invalid_node_name_error() {
+    fn parse_portable(name: &str) -> Result<&NodeName, InvalidNodeNameError> {
+        let name = NodeName::from_str(name)?;
+        name.validate_for(PortabilityProfile::Portable)?;
+        Ok(name)
+    }
+
+    assert_eq!(parse_portable("foo.txt").unwrap(), "foo.txt");
+    assert_eq!(parse_portable(""), Err(InvalidNodeNameError::Empty));
+    assert_eq!(
+        parse_portable("aux.txt"),
+        Err(InvalidNodeNameError::NotPortable(PortabilityError::ReservedName))
+    );
+}
+
+#[test]
+fn sanitize_unchanged() {
+    let (name, changed) =
+        NodeNameBuf::sanitize("notes.txt", &SanitizeOptions::default());
+    assert_eq!(name.as_str(), "notes.txt");
+    assert!(!changed);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn sanitize_invalid_characters() {
+    let options = SanitizeOptions::default();
+    let (name, changed) = NodeNameBuf::sanitize("a/b\0c", &options);
+    assert_eq!(name.as_str(), "a_b_c");
+    assert!(changed);
+
+    let options = options.replacement(None);
+    let (name, _) = NodeNameBuf::sanitize("a/b\0c", &options);
+    assert_eq!(name.as_str(), "abc");
+}
+
+#[test]
+fn sanitize_dots_and_empty() {
+    let options = SanitizeOptions::default();
+    assert_eq!(NodeNameBuf::sanitize("", &options).0.as_str(), "_");
+    assert_eq!(NodeNameBuf::sanitize(".", &options).0.as_str(), "_");
+    assert_eq!(NodeNameBuf::sanitize("..", &options).0.as_str(), "__");
+}
+
+#[test]
+fn sanitize_windows_profile() {
+    let options =
+        SanitizeOptions::default().profile(PortabilityProfile::Windows);
+    for (input, expected) in [
+        ("aux.txt", "aux_.txt"),
+        ("CON", "CON_"),
+        ("foo. . ", "foo"),
+        ("a:b?", "a_b_"),
+        ("...", "_"),
+    ] {
+        let (name, changed) = NodeNameBuf::sanitize(input, &options);
+        assert_eq!(name.as_str(), expected);
+        assert!(changed);
+        assert_eq!(name.validate_for(PortabilityProfile::Windows), Ok(()));
+    }
+}
+
+#[test]
+fn sanitize_truncate_then_escape_reserved_name() {
+    let options = SanitizeOptions::default()
+        .max_len(3)
+        .profile(PortabilityProfile::Windows);
+    let (name, _) = NodeNameBuf::sanitize("auxiliary", &options);
+    assert_eq!(name.as_str(), "au_");
+    assert_eq!(name.validate_for(PortabilityProfile::Windows), Ok(()));
+
+    let options = options.max_len(8);
+    let (name, _) = NodeNameBuf::sanitize("com1.longext", &options);
+    assert!(name.len() <= 8, "{name:?}");
+    assert_eq!(name.validate_for(PortabilityProfile::Windows), Ok(()));
+}
+
+#[test]
+fn sanitize_tiny_max_len() {
+    for max_len in [0, 1] {
+        let options = SanitizeOptions::default().max_len(max_len);
+        for (input, expected) in
+            [("notes.txt", "n"), ("", "_"), (".", "_"), ("..", "_")]
+        {
+            let (name, _) = NodeNameBuf::sanitize(input, &options);
+            assert_eq!(name.as_str(), expected, "{input:?}");
+        }
+    }
+
+    // The limit is raised to the length of the escape character.
+    let options = SanitizeOptions::default().replacement(Some('é')).max_len(1);
+    assert_eq!(NodeNameBuf::sanitize("", &options).0.as_str(), "é");
+    assert_eq!(NodeNameBuf::sanitize("..", &options).0.as_str(), "é");
+}
+
+#[test]
+fn sanitize_extension_only() {
+    let options = SanitizeOptions::default().max_len(4);
+    let (name, _) = NodeNameBuf::sanitize(".gitignore", &options);
+    assert_eq!(name.as_str(), ".git");
+
+    let options = options.profile(PortabilityProfile::Windows);
+    let (name, _) = NodeNameBuf::sanitize(".aux", &options);
+    assert_eq!(name.as_str(), ".aux");
+    let (name, _) = NodeNameBuf::sanitize(". . .", &options);
+    assert_eq!(name.as_str(), "_");
+}
+
+#[test]
+fn sanitize_reserved_name_with_long_escape() {
+    // The stem alone can't make room for the escape character, so the
+    // extension is shortened too.
+    let options = SanitizeOptions::default()
+        .replacement(Some('\u{1f600}'))
+        .max_len(6)
+        .profile(PortabilityProfile::Portable);
+    let (name, _) = NodeNameBuf::sanitize("con.ab", &options);
+    assert_eq!(name.as_str(), "\u{1f600}.a");
+
+    // Escaping with a digit would turn `COM` back into a reserved name.
+    let options = SanitizeOptions::default()
+        .replacement(Some('1'))
+        .max_len(4)
+        .profile(PortabilityProfile::Windows);
+    let (name, _) = NodeNameBuf::sanitize("COM5", &options);
+    assert_eq!(name.as_str(), "1COM");
+}
+
+#[test]
+fn sanitize_always_valid() {
+    let inputs = [
+        "",
+        ".",
+        "..",
+        "...",
+        " . ",
+        "aux",
+        "CON.txt",
+        "com1.tar.gz",
+        "LPT\u{b9}",
+        "nul .c",
+        "a:b?c",
+        "foo. ",
+        ".gitignore",
+        "\0\u{1}",
+        "\u{e9}\u{e9}\u{e9}.txt",
+        "a/b/c",
+        "con.\u{e9}\u{e9}",
+    ];
+    let replacements = [Some('_'), Some('1'), Some('N'), Some('\u{e9}'), None];
+    let profiles = [
+        None,
+        Some(PortabilityProfile::Posix),
+        Some(PortabilityProfile::Windows),
+        Some(PortabilityProfile::MacOS),
+        Some(PortabilityProfile::Portable),
+    ];
+
+    for input in inputs {
+        for replacement in replacements {
+            for profile in profiles {
+                for max_len in (0..10).map(Some).chain([None]) {
+                    let mut options =
+                        SanitizeOptions::default().replacement(replacement);
+                    if let Some(profile) = profile {
+                        options = options.profile(profile);
+                    }
+                    if let Some(max_len) = max_len {
+                        options = options.max_len(max_len);
+                    }
+
+                    let (name, _) = NodeNameBuf::sanitize(input, &options);
+                    let context = format!("{input:?} with {options:?}");
+                    assert!(NodeName::from_str(&name).is_ok(), "{context}");
+                    if let Some(profile) = profile {
+                        assert_eq!(
+                            name.validate_for(profile),
+                            Ok(()),
+                            "{context}"
+                        );
+                    }
+                    if let Some(max_len) = max_len {
+                        assert!(name.len() <= max_len.max(2), "{context}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn sanitize_truncate_preserves_extension() {
+    let options = SanitizeOptions::default().max_len(8);
+    let (name, changed) = NodeNameBuf::sanitize("abcdefgh.txt", &options);
+    assert_eq!(name.as_str(), "abcd.txt");
+    assert!(changed);
+
+    // Truncation never splits a character.
+    let (name, _) = NodeNameBuf::sanitize("ééééé.txt", &options);
+    assert_eq!(name.as_str(), "éé.txt");
+
+    // The whole name is truncated if the extension doesn't fit.
+    let (name, _) = NodeNameBuf::sanitize("a.verylongextension", &options);
+    assert_eq!(name.as_str(), "a.verylo");
+}
+
+#[test]
+fn case_insensitive() {
+    assert_eq!(
+        CaseInsensitive(node!("README.md")),
+        CaseInsensitive(node!("readme.MD"))
+    );
+    assert_ne!(CaseInsensitive(node!("ı")), CaseInsensitive(node!("I")));
+}
+
+#[test]
+fn node_buf_macro() {
+    const SHORT: NodeNameBuf = node_buf!("Cargo.toml");
+    const LONG: NodeNameBuf =
+        node_buf!("a-file-name-longer-than-23-bytes.txt");
+    assert_eq!(SHORT.as_str(), "Cargo.toml");
+    assert_eq!(LONG.as_str(), "a-file-name-longer-than-23-bytes.txt");
+}
+
+#[test]
+fn stack_node_name_buf() {
+    let name = StackNodeNameBuf::<8>::new(node!("foo.txt")).unwrap();
+    assert_eq!(name, "foo.txt");
+    assert_eq!(NodeNameBuf::from(name).as_str(), "foo.txt");
+
+    let err = StackNodeNameBuf::<4>::new(node!("foo.txt")).unwrap_err();
+    assert_eq!(err.capacity(), 4);
+    assert_eq!(err.required_len(), 7);
+}
+
+#[test]
+fn to_nfd_keeps_names_that_would_become_invalid() {
+    // `≮` decomposes into `<` followed by U+0338, and `<` is invalid on
+    // Windows.
+    let name = NodeName::from_str("a\u{226E}b").unwrap();
+    let nfd = name.to_nfd();
+    assert!(NodeName::from_str(nfd.as_str()).is_ok());
+    if cfg!(target_os = "windows") {
+        assert_eq!(nfd.as_str(), name.as_str());
+    } else {
+        assert_eq!(nfd.as_str(), "a<\u{338}b");
+    }
+    assert_eq!(nfd.to_nfc().as_str(), name.as_str());
+}
@@ -0,0 +1,110 @@
+use abs_path::{AbsPath, NodeName, PathArena, path};
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_arena_push_get() {
+    let mut arena = PathArena::new();
+
+    let foo = arena.push(path!("/foo"));
+    let bar = arena.push(path!("/bar"));
+
+    assert_eq!(arena.len(), 2);
+    assert_eq!(&arena[foo], "/foo");
+    assert_eq!(arena.get(bar), Some(path!("/bar")));
+    assert_eq!(arena.buffer_len(), "/foo/bar".len());
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_arena_push_shares_ancestors() {
+    let mut arena = PathArena::new();
+
+    let first = arena.extend_from_iter([
+        AbsPath::root(),
+        path!("/foo"),
+        path!("/foo/bar"),
+        path!("/foo"),
+        path!("/foo/bar/baz.txt"),
+    ]);
+
+    assert_eq!(first.index(), 0);
+    assert_eq!(arena.buffer_len(), "/foo/bar/baz.txt".len());
+    assert_eq!(
+        arena.iter().map(|(_, path)| path.as_str()).collect::<Vec<_>>(),
+        ["/", "/foo", "/foo/bar", "/foo", "/foo/bar/baz.txt"]
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_arena_sort_dedup() {
+    let mut arena = [
+        path!("/a-b"),
+        path!("/a/b"),
+        path!("/c"),
+        path!("/a"),
+        path!("/a/b"),
+    ]
+    .into_iter()
+    .collect::<PathArena>();
+
+    arena.sort();
+    arena.dedup();
+
+    assert_eq!(
+        arena.iter().map(|(_, path)| path.as_str()).collect::<Vec<_>>(),
+        ["/a", "/a/b", "/a-b", "/c"]
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_arena_share_ancestors() {
+    let paths = [
+        path!("/src/lib.rs"),
+        path!("/src"),
+        path!("/Cargo.toml"),
+        path!("/src/a"),
+        path!("/src/a/b.rs"),
+        path!("/src/lib.rs"),
+        AbsPath::root(),
+    ];
+
+    let mut arena = paths.into_iter().collect::<PathArena>();
+    let ids = arena.iter().map(|(id, _)| id).collect::<Vec<_>>();
+
+    arena.share_ancestors();
+
+    assert_eq!(
+        arena.buffer_len(),
+        "/src/lib.rs".len() + "/Cargo.toml".len() + "/src/a/b.rs".len()
+    );
+
+    for (id, path) in ids.into_iter().zip(paths) {
+        assert_eq!(&arena[id], path);
+    }
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_arena_share_ancestors_siblings() {
+    let dir = path!("/home/user/project/src");
+    let files = (0..100)
+        .map(|idx| {
+            let name = format!("file{idx}.rs");
+            dir.join(<&NodeName>::try_from(&*name).unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    let mut arena = files.iter().collect::<PathArena>();
+    arena.push(dir);
+    arena.share_ancestors();
+
+    // The directory shares the bytes of one of the files, but the files
+    // can't share their common prefix with each other.
+    assert_eq!(
+        arena.buffer_len(),
+        files.iter().map(|path| path.len()).sum::<usize>()
+    );
+    assert_eq!(&arena[arena.iter().last().unwrap().0], dir);
+}
@@ -0,0 +1,134 @@
+use abs_path::{
+    AbsPath,
+    AbsPathBuf,
+    InvalidNodeNameError,
+    PathDecodeError,
+    PathDecoder,
+    PathEncoder,
+    path,
+};
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_codec_round_trip() {
+    let paths = [
+        AbsPath::root(),
+        path!("/foo"),
+        path!("/foo/bar"),
+        path!("/foo/bar/baz.rs"),
+        path!("/foo/qux.rs"),
+        path!("/zed"),
+        path!("/foo/bar"),
+    ];
+
+    let mut encoder = PathEncoder::new();
+    for path in paths {
+        encoder.push(path);
+    }
+    let bytes = encoder.finish();
+
+    let decoded = PathDecoder::new(&bytes)
+        .unwrap()
+        .collect::<Result<Vec<AbsPathBuf>, _>>()
+        .unwrap();
+
+    assert_eq!(decoded, paths);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_codec_front_coding() {
+    let paths = [
+        path!("/home/user/project/src/lib.rs"),
+        path!("/home/user/project/src/main.rs"),
+        path!("/home/user/project/src/utils/mod.rs"),
+        path!("/home/user/project/tests/it.rs"),
+    ];
+
+    let mut encoder = PathEncoder::new();
+    for path in paths {
+        encoder.push(path);
+    }
+    let bytes = encoder.finish();
+
+    let strings_len = paths.iter().map(|path| path.len()).sum::<usize>();
+    assert!(bytes.len() < strings_len);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_codec_borrowed_components() {
+    let mut encoder = PathEncoder::new();
+    encoder.push(path!("/a/b")).push(path!("/a/c"));
+    let bytes = encoder.finish();
+
+    let mut decoder = PathDecoder::new(&bytes).unwrap();
+
+    let components = decoder.next_components().unwrap().unwrap();
+    assert_eq!(
+        components.iter().map(|name| name.as_str()).collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+
+    let components = decoder.next_components().unwrap().unwrap();
+    assert_eq!(
+        components.iter().map(|name| name.as_str()).collect::<Vec<_>>(),
+        ["a", "c"]
+    );
+
+    assert!(decoder.next_components().is_none());
+}
+
+#[test]
+fn path_codec_header() {
+    assert_eq!(
+        PathDecoder::new(b"nope\x01").unwrap_err(),
+        PathDecodeError::InvalidHeader
+    );
+    assert_eq!(
+        PathDecoder::new(b"apth").unwrap_err(),
+        PathDecodeError::InvalidHeader
+    );
+    assert_eq!(
+        PathDecoder::new(b"apth\x02").unwrap_err(),
+        PathDecodeError::UnsupportedVersion(2)
+    );
+    assert_eq!(PathDecoder::new(b"apth\x01").unwrap().count(), 0);
+}
+
+#[test]
+fn path_codec_errors() {
+    let decode = |bytes: &[u8]| {
+        PathDecoder::new(bytes).unwrap().next().unwrap().unwrap_err()
+    };
+
+    // Shares one component with an empty previous path.
+    assert_eq!(
+        decode(b"apth\x01\x01\x00"),
+        PathDecodeError::InvalidSharedDepth { byte_offset: 5 }
+    );
+
+    // Declares a 3-byte component but only has 2 bytes.
+    assert_eq!(
+        decode(b"apth\x01\x00\x01\x03ab"),
+        PathDecodeError::UnexpectedEof
+    );
+
+    assert_eq!(
+        decode(b"apth\x01\x00\x01\x02.."),
+        PathDecodeError::InvalidComponent {
+            byte_offset: 8,
+            err: InvalidNodeNameError::DoubleDot,
+        }
+    );
+
+    assert_eq!(
+        decode(b"apth\x01\x00\x01\x01\xff"),
+        PathDecodeError::NotUtf8 { byte_offset: 8 }
+    );
+
+    assert_eq!(
+        decode(b"apth\x01\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01"),
+        PathDecodeError::InvalidVarint { byte_offset: 5 }
+    );
+}
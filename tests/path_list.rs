@@ -0,0 +1,157 @@
+use abs_path::{
+    AbsPath,
+    InvalidAbsPathError,
+    NormalizeError,
+    PathList,
+    PathListErrorKind,
+    RecordSeparator,
+    path,
+};
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_list_nul() {
+    let buf = b"/foo\0/foo/bar\0\0/baz\0";
+
+    let paths = PathList::new(buf, RecordSeparator::Nul)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(paths, [path!("/foo"), path!("/foo/bar"), path!("/baz")]);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_list_crlf() {
+    let buf = b"/foo\r\n\r\n/foo/bar\r\n/baz";
+
+    let paths = PathList::new(buf, RecordSeparator::Newline)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(paths, [path!("/foo"), path!("/foo/bar"), path!("/baz")]);
+
+    // A `\r` is only stripped before a newline.
+    let mut list = PathList::new(b"/foo\r\0", RecordSeparator::Nul);
+    assert_eq!(list.next().unwrap().unwrap(), "/foo\r");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_list_errors() {
+    let buf = b"/foo\n\nfoo\n/foo//bar\n/\xff";
+
+    let mut list = PathList::new(buf, RecordSeparator::Newline);
+
+    assert_eq!(list.next().unwrap().unwrap(), "/foo");
+
+    let err = list.next().unwrap().unwrap_err();
+    assert_eq!(err.record(), 3);
+    assert_eq!(err.byte_offset(), 6);
+    assert_eq!(
+        err.kind(),
+        PathListErrorKind::Invalid(InvalidAbsPathError::NotAbsolute)
+    );
+
+    let err = list.next().unwrap().unwrap_err();
+    assert_eq!(err.record(), 4);
+    assert_eq!(err.byte_offset(), 10);
+    assert_eq!(
+        err.kind(),
+        PathListErrorKind::Invalid(InvalidAbsPathError::EmptyComponent {
+            byte_offset: 5,
+            component_idx: 1,
+        })
+    );
+
+    let err = list.next().unwrap().unwrap_err();
+    assert_eq!(err.record(), 5);
+    assert_eq!(err.kind(), PathListErrorKind::NotUtf8);
+
+    assert!(list.next().is_none());
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn relative_path_list() {
+    let buf = b"src/lib.rs\0./src/../Cargo.toml\0/etc/hosts\0../../..\0";
+
+    let mut list =
+        PathList::new(buf, RecordSeparator::Nul).relative_to(path!("/repo"));
+
+    let mut next = || list.next_path().map(|res| res.map(|p| p.into_owned()));
+
+    assert_eq!(next().unwrap().unwrap(), "/repo/src/lib.rs");
+    assert_eq!(next().unwrap().unwrap(), "/repo/Cargo.toml");
+    assert_eq!(next().unwrap().unwrap(), "/etc/hosts");
+
+    let err = next().unwrap().unwrap_err();
+    assert_eq!(err.record(), 4);
+    assert_eq!(
+        err.kind(),
+        PathListErrorKind::Normalize(NormalizeError::EscapesRoot)
+    );
+
+    assert!(next().is_none());
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn relative_path_list_escape() {
+    let buf = b"../other\nsrc/../../repo/lib.rs\n/etc/hosts\n";
+
+    let mut list = PathList::new(buf, RecordSeparator::Newline)
+        .relative_to(path!("/repo"));
+
+    let err = list.next_path().unwrap().unwrap_err();
+    assert_eq!(err.record(), 1);
+    assert_eq!(err.kind(), PathListErrorKind::EscapesBase);
+    assert_eq!(*list.next_path().unwrap().unwrap(), *path!("/repo/lib.rs"));
+    assert_eq!(*list.next_path().unwrap().unwrap(), *path!("/etc/hosts"));
+
+    // A sibling sharing a prefix with the base is still outside of it.
+    let mut list = PathList::new(b"../repository", RecordSeparator::Newline)
+        .relative_to(path!("/repo"));
+    assert_eq!(
+        list.next_path().unwrap().unwrap_err().kind(),
+        PathListErrorKind::EscapesBase
+    );
+
+    let mut list = PathList::new(buf, RecordSeparator::Newline)
+        .relative_to(path!("/repo"))
+        .allow_escape(true);
+    assert_eq!(*list.next_path().unwrap().unwrap(), *path!("/other"));
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn path_list_normalizing() {
+    let buf = b"/foo/./bar\n/foo//baz/..\n";
+
+    let mut list = PathList::new(buf, RecordSeparator::Newline);
+    assert!(list.next().unwrap().is_err());
+
+    let mut list = PathList::new(buf, RecordSeparator::Newline)
+        .relative_to(AbsPath::root());
+    assert_eq!(*list.next_path().unwrap().unwrap(), *path!("/foo/bar"));
+    assert_eq!(*list.next_path().unwrap().unwrap(), *path!("/foo"));
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn relative_path_list_invalid_character_offset() {
+    let buf = b"foo/b\0r";
+
+    let mut list = PathList::new(buf, RecordSeparator::Newline)
+        .relative_to(AbsPath::root());
+
+    let err = list.next_path().unwrap().unwrap_err();
+
+    assert_eq!(
+        err.kind(),
+        PathListErrorKind::Normalize(NormalizeError::InvalidCharacter {
+            byte_offset: 5,
+            ch: '\0',
+        })
+    );
+}
@@ -0,0 +1,156 @@
+use abs_path::{
+    AbsPath,
+    NormalizeError,
+    ResolvePathErrorKind,
+    ResolvePaths,
+    path,
+};
+
+#[derive(ResolvePaths)]
+struct Config {
+    #[resolve]
+    root: String,
+
+    #[resolve]
+    cache_dir: Option<String>,
+
+    #[resolve(allow_escape)]
+    shared: &'static str,
+
+    #[allow(dead_code)]
+    name: String,
+}
+
+#[derive(ResolvePaths)]
+struct ManifestConfig {
+    #[resolve(must_exist)]
+    manifest: &'static str,
+}
+
+#[derive(ResolvePaths)]
+struct HomeConfig {
+    #[resolve(expand_tilde)]
+    home: &'static str,
+}
+
+/// Checks that the items generated by `#[derive(ResolvePaths)]` are
+/// documented.
+#[deny(missing_docs)]
+pub mod documented {
+    /// A configuration.
+    #[derive(abs_path::ResolvePaths)]
+    pub struct Config {
+        /// The root directory.
+        #[resolve]
+        pub root: String,
+
+        /// The cache directory.
+        #[resolve]
+        pub cache_dir: Option<String>,
+    }
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn resolve_paths_relative() {
+    let config = Config {
+        root: "./src/../lib".into(),
+        cache_dir: Some("/var/cache/app/".into()),
+        shared: "../shared",
+        name: "app".into(),
+    };
+
+    let paths = config.resolve_paths(path!("/etc/app")).unwrap();
+
+    assert_eq!(paths.root, "/etc/app/lib");
+    assert_eq!(paths.cache_dir.as_deref(), Some(path!("/var/cache/app")));
+    assert_eq!(paths.shared, "/etc/shared");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn resolve_paths_none() {
+    let config = Config {
+        root: "lib".into(),
+        cache_dir: None,
+        shared: "shared",
+        name: "app".into(),
+    };
+
+    let paths = config.resolve_paths(AbsPath::root()).unwrap();
+
+    assert_eq!(paths.root, "/lib");
+    assert_eq!(paths.cache_dir, None);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn resolve_paths_aggregates_errors() {
+    let config = Config {
+        root: "../../lib".into(),
+        cache_dir: Some("cache\0".into()),
+        shared: "../../../shared",
+        name: "app".into(),
+    };
+
+    let err = config.resolve_paths(path!("/etc/app")).unwrap_err();
+
+    let errors = err
+        .errors()
+        .iter()
+        .map(|err| (err.field(), err.kind()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        [
+            ("root", ResolvePathErrorKind::EscapesBase),
+            (
+                "cache_dir",
+                ResolvePathErrorKind::Invalid(
+                    NormalizeError::InvalidCharacter {
+                        byte_offset: 5,
+                        ch: '\0'
+                    }
+                )
+            ),
+            (
+                "shared",
+                ResolvePathErrorKind::Invalid(NormalizeError::EscapesRoot)
+            ),
+        ]
+    );
+
+    assert!(err.to_string().contains("`cache_dir`"));
+}
+
+#[test]
+fn resolve_paths_must_exist() {
+    let base = <&AbsPath>::try_from(env!("CARGO_MANIFEST_DIR")).unwrap();
+
+    let config = ManifestConfig { manifest: "Cargo.toml" };
+    let paths = config.resolve_paths(base).unwrap();
+    assert!(paths.manifest.starts_with(base));
+
+    let config = ManifestConfig { manifest: "Cargo.lock.missing" };
+    let err = config.resolve_paths(base).unwrap_err();
+    assert_eq!(err.errors()[0].field(), "manifest");
+    assert_eq!(err.errors()[0].kind(), ResolvePathErrorKind::DoesNotExist);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn resolve_paths_expand_tilde() {
+    let Some(home) = std::env::home_dir() else { return };
+    let Ok(home) = <&AbsPath>::try_from(&*home) else { return };
+
+    let config = HomeConfig { home: "~/.config" };
+    let paths = config.resolve_paths(path!("/etc")).unwrap();
+    assert!(paths.home.starts_with(home));
+    assert_eq!(paths.home.node_name().unwrap(), ".config");
+
+    // A tilde that's not followed by a separator is a regular file name.
+    let config = HomeConfig { home: "~foo" };
+    let paths = config.resolve_paths(path!("/etc")).unwrap();
+    assert_eq!(paths.home, "/etc/~foo");
+}
@@ -0,0 +1,64 @@
+use abs_path::{
+    AbsPathBuf,
+    ArchivedAbsPathBuf,
+    ArchivedNodeNameBuf,
+    NodeNameBuf,
+    node,
+    path,
+};
+use rkyv::rancor::Error;
+use rkyv::vec::ArchivedVec;
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn rkyv_abs_path_buf() {
+    let paths: Vec<AbsPathBuf> = vec![
+        path!("/").into(),
+        path!("/foo").into(),
+        path!("/a/path/long/enough/to/be/stored/out/of/line").into(),
+    ];
+
+    let bytes = rkyv::to_bytes::<Error>(&paths).unwrap();
+
+    let archived =
+        rkyv::access::<ArchivedVec<ArchivedAbsPathBuf>, Error>(&bytes)
+            .unwrap();
+
+    assert_eq!(archived.len(), 3);
+    assert!(archived[0].is_root());
+    assert_eq!(archived[1].node_name(), Some(node!("foo")));
+    assert_eq!(archived[2], *paths[2]);
+
+    let deserialized =
+        rkyv::deserialize::<Vec<AbsPathBuf>, Error>(archived).unwrap();
+    assert_eq!(deserialized, paths);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn rkyv_node_name_buf() {
+    let name = node!("foo.txt").to_owned();
+
+    let bytes = rkyv::to_bytes::<Error>(&name).unwrap();
+    let archived = rkyv::access::<ArchivedNodeNameBuf, Error>(&bytes).unwrap();
+
+    assert_eq!(*archived, *node!("foo.txt"));
+    assert_eq!(
+        rkyv::deserialize::<NodeNameBuf, Error>(archived).unwrap(),
+        name
+    );
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn rkyv_validation() {
+    for invalid in ["foo/bar", "/foo//bar", "/foo/./bar", "/foo/.."] {
+        let bytes = rkyv::to_bytes::<Error>(&invalid.to_owned()).unwrap();
+        assert!(rkyv::access::<ArchivedAbsPathBuf, Error>(&bytes).is_err());
+    }
+
+    for invalid in ["", ".", "..", "foo/bar"] {
+        let bytes = rkyv::to_bytes::<Error>(&invalid.to_owned()).unwrap();
+        assert!(rkyv::access::<ArchivedNodeNameBuf, Error>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use abs_path::{AbsPath, AbsPathBuf, NodeName, NodeNameBuf};
+use regex::Regex;
+use schemars::{JsonSchema, schema_for};
+
+fn pattern<T: JsonSchema>() -> Regex {
+    let schema = schema_for!(T);
+    Regex::new(schema.get("pattern").unwrap().as_str().unwrap()).unwrap()
+}
+
+#[test]
+fn schemars_format() {
+    let schema = schema_for!(AbsPathBuf);
+    assert_eq!(schema.get("type").unwrap(), "string");
+    assert_eq!(schema.get("format").unwrap(), "abs-path");
+
+    let schema = schema_for!(NodeNameBuf);
+    assert_eq!(schema.get("type").unwrap(), "string");
+    assert_eq!(schema.get("format").unwrap(), "node-name");
+
+    assert_eq!(schema_for!(&AbsPath), schema_for!(AbsPathBuf));
+    assert_eq!(schema_for!(&NodeName), schema_for!(NodeNameBuf));
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn schemars_abs_path_pattern() {
+    let pattern = pattern::<AbsPathBuf>();
+
+    for valid in ["/", "/foo", "/foo/bar.rs", "/.git/...", "/a/.b/..c/ü"] {
+        assert!(pattern.is_match(valid), "{valid:?}");
+        assert!(AbsPath::from_str(valid).is_ok());
+    }
+
+    for invalid in
+        ["", "foo", "//", "/foo/", "/foo//bar", "/./foo", "/foo/..", "/f\0o"]
+    {
+        assert!(!pattern.is_match(invalid), "{invalid:?}");
+        assert!(AbsPath::from_str(invalid).is_err());
+    }
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn schemars_node_name_pattern() {
+    let pattern = pattern::<NodeNameBuf>();
+
+    for valid in ["foo", ".git", "...", "..c", "a b"] {
+        assert!(pattern.is_match(valid), "{valid:?}");
+        assert!(NodeName::from_str(valid).is_ok());
+    }
+
+    for invalid in ["", ".", "..", "foo/bar", "f\0o"] {
+        assert!(!pattern.is_match(invalid), "{invalid:?}");
+        assert!(NodeName::from_str(invalid).is_err());
+    }
+}
+
+#[test]
+fn schemars_shared_definition() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Config<'a> {
+        root: AbsPathBuf,
+        borrowed: &'a AbsPath,
+        name: NodeNameBuf,
+    }
+
+    let schema = schema_for!(Config);
+    let defs = schema.get("$defs").unwrap().as_object().unwrap();
+    assert_eq!(defs.len(), 2, "{defs:?}");
+    assert!(defs.contains_key("AbsPath"));
+    assert!(defs.contains_key("NodeName"));
+}
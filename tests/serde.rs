@@ -0,0 +1,201 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use abs_path::serde_as::NormalizeSeed;
+use abs_path::serde_as::relative_to_base::{Base, RelativeTo};
+use abs_path::{AbsPath, AbsPathBuf, GenericAbsPathBuf, NodeName, path};
+use serde::{Deserialize, Serialize};
+
+struct Project;
+
+impl Base for Project {
+    const PATH: &'static AbsPath = path!("/home/user/project");
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Adapters {
+    #[serde(with = "abs_path::serde_as::as_components")]
+    components: AbsPathBuf,
+
+    #[serde(with = "RelativeTo::<Project>")]
+    relative: AbsPathBuf,
+
+    #[serde(with = "abs_path::serde_as::as_file_uri")]
+    uri: AbsPathBuf,
+
+    #[serde(with = "abs_path::serde_as::lossy_os")]
+    os: AbsPathBuf,
+}
+
+#[derive(Deserialize)]
+struct Borrowed<'a> {
+    #[serde(borrow, with = "abs_path::serde_as::cow")]
+    path: Cow<'a, AbsPath>,
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn serde_adapters_round_trip() {
+    let adapters = Adapters {
+        components: path!("/foo/bar").to_owned(),
+        relative: path!("/home/user/project/src/lib.rs").to_owned(),
+        uri: path!("/foo/a b/ü").to_owned(),
+        os: path!("/foo").to_owned(),
+    };
+
+    let json = serde_json::to_string(&adapters).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"components":["foo","bar"],"relative":"src/lib.rs","uri":"file:///foo/a%20b/%C3%BC","os":"/foo"}"#
+    );
+
+    assert_eq!(serde_json::from_str::<Adapters>(&json).unwrap(), adapters);
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn serde_adapters_errors() {
+    #[derive(Debug, Serialize)]
+    struct Relative {
+        #[serde(with = "RelativeTo::<Project>")]
+        path: AbsPathBuf,
+    }
+
+    let err = serde_json::to_string(&Relative { path: path!("/etc").into() })
+        .unwrap_err();
+    assert!(err.to_string().contains("not inside"));
+
+    let err = serde_json::from_str::<Adapters>(
+        r#"{"components":["foo",".."],"relative":"","uri":"file:///","os":"/"}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("invalid component"));
+
+    let err = serde_json::from_str::<Adapters>(
+        r#"{"components":[],"relative":"","uri":"file://host/foo","os":"/"}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("non-local host"));
+
+    let err = serde_json::from_str::<Adapters>(
+        r#"{"components":[],"relative":"","uri":"file:///a%2Fb","os":"/"}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains(r#"invalid component "a/b""#), "{err}");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn serde_file_uri_localhost_and_trailing_slash() {
+    let adapters = serde_json::from_str::<Adapters>(
+        r#"{"components":[],"relative":"","uri":"file://localhost/foo/","os":"/"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(adapters.components, "/");
+    assert_eq!(adapters.relative, "/home/user/project");
+    assert_eq!(adapters.uri, "/foo");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn serde_lossy_os() {
+    let adapters = serde_json::from_str::<Adapters>(
+        r#"{"components":[],"relative":"","uri":"file:///","os":[47,102,255]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(adapters.os, "/f\u{FFFD}");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn serde_borrowed() {
+    let path = serde_json::from_str::<&AbsPath>(r#""/foo""#).unwrap();
+    assert_eq!(path, "/foo");
+
+    // Escapes force serde_json to unescape the string into a temporary
+    // buffer, so it can't be borrowed.
+    let err = serde_json::from_str::<&AbsPath>(r#""/fo\u006f""#).unwrap_err();
+    assert!(err.to_string().contains("abs_path::serde_as::cow"));
+
+    // Readers don't keep the input in memory, so nothing can be borrowed.
+    let mut de = serde_json::Deserializer::from_reader(&br#""/foo""#[..]);
+    let err = <&AbsPath>::deserialize(&mut de).unwrap_err();
+    assert!(err.to_string().contains("into an AbsPathBuf"));
+    assert!(err.to_string().contains("abs_path::serde_as::cow"));
+
+    let name = serde_json::from_str::<&NodeName>(r#""foo""#).unwrap();
+    assert_eq!(name, "foo");
+    let mut de = serde_json::Deserializer::from_reader(&br#""foo""#[..]);
+    let err = <&NodeName>::deserialize(&mut de).unwrap_err();
+    assert!(err.to_string().contains("into a NodeNameBuf"));
+
+    let borrowed =
+        serde_json::from_str::<Borrowed>(r#"{"path":"/foo"}"#).unwrap();
+    assert!(matches!(borrowed.path, Cow::Borrowed(path) if path == "/foo"));
+
+    let owned =
+        serde_json::from_str::<Borrowed>(r#"{"path":"/fo\u006f"}"#).unwrap();
+    assert!(matches!(owned.path, Cow::Owned(ref path) if path == "/foo"));
+}
+
+#[derive(Debug, Deserialize)]
+struct Normalized {
+    #[serde(with = "abs_path::serde_as::normalize")]
+    path: AbsPathBuf,
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn serde_normalize() {
+    let normalized = serde_json::from_str::<Normalized>(
+        r#"{"path":"/srv//data/./cache/"}"#,
+    )
+    .unwrap();
+    assert_eq!(normalized.path, "/srv/data/cache");
+
+    let err =
+        serde_json::from_str::<Normalized>(r#"{"path":"srv"}"#).unwrap_err();
+    assert!(err.to_string().contains("not absolute"), "{err}");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn serde_normalize_seed() {
+    use serde::de::DeserializeSeed;
+
+    let seed = NormalizeSeed::new(path!("/etc/app"));
+
+    let mut de = serde_json::Deserializer::from_str(r#""../data/./x""#);
+    assert_eq!(seed.deserialize(&mut de).unwrap(), "/etc/data/x");
+
+    let mut de = serde_json::Deserializer::from_str(r#""/var//log""#);
+    assert_eq!(seed.deserialize(&mut de).unwrap(), "/var/log");
+
+    let seed = NormalizeSeed::<Arc<str>>::new_in(path!("/etc/app"));
+    let mut de = serde_json::Deserializer::from_str(r#""./data""#);
+    let path: GenericAbsPathBuf<Arc<str>> = seed.deserialize(&mut de).unwrap();
+    assert_eq!(path, "/etc/app/data");
+
+    let mut de = serde_json::Deserializer::from_str(r#""ok/n\u0000t""#);
+    let err = seed.deserialize(&mut de).unwrap_err();
+    assert!(err.to_string().contains("at byte range 4..5"), "{err}");
+}
+
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn serde_storage() {
+    let path =
+        serde_json::from_str::<GenericAbsPathBuf<Arc<str>>>(r#""/foo""#);
+    assert_eq!(path.unwrap(), "/foo");
+
+    // Unescaping produces an owned string, which is reused by `String`.
+    let path =
+        serde_json::from_str::<GenericAbsPathBuf<String>>(r#""/fo\u006f""#);
+    assert_eq!(path.unwrap(), "/foo");
+
+    let err = serde_json::from_str::<AbsPathBuf>(r#""/foo/""#).unwrap_err();
+    assert!(err.to_string().contains("is empty"), "{err}");
+}
@@ -0,0 +1,5 @@
+#[test]
+#[cfg_attr(target_os = "windows", ignore)]
+fn ui() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,5 @@
+use abs_path::node;
+
+fn main() {
+    let _ = node!("foo/bar");
+}
@@ -0,0 +1,5 @@
+use abs_path::path;
+
+fn main() {
+    let _ = path!("/foo/../bar");
+}